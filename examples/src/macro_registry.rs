@@ -0,0 +1,45 @@
+use xxljob_sdk_rs::{xxl_job, xxl_log, JobContext, XxlClientBuilder};
+
+/// 异步任务处理器，构建客户端时自动注册
+#[xxl_job("demoJobHandler")]
+async fn demo_job(context: JobContext) -> anyhow::Result<()> {
+    log::info!(
+        "async|demo_job job info; job_id:{}, log_id:{}, job_param:{:?}",
+        &context.job_id,
+        &context.log_id,
+        &context.job_param
+    );
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    xxl_log!(context, "async|demo_job job process done");
+    Ok(())
+}
+
+/// 同步任务处理器，在同步任务线程池中运行
+#[xxl_job("demoJobHandler2")]
+fn demo_sync_job(context: JobContext) -> anyhow::Result<()> {
+    for i in 0..5 {
+        if context.is_cancelled() {
+            break;
+        }
+        xxl_log!(context, "sync|demo_sync_job step:{}", i);
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    std::env::set_var("RUST_LOG", "INFO");
+    env_logger::init();
+    log::info!("macro registry start");
+    let client = XxlClientBuilder::new("http://127.0.0.1:8725/xxl-job-admin".to_string())
+        .set_access_token("default_token".to_string())
+        .set_log_path("xxl-rs-logs".to_string())
+        .set_app_name("xxl-job-executor-sample".to_string())
+        .build()?;
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for event");
+    client.shutdown(std::time::Duration::from_secs(10)).await?;
+    Ok(())
+}
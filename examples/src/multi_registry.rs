@@ -1,13 +1,13 @@
 use async_trait::async_trait;
 use std::sync::Arc;
-use xxljob_sdk_rs::{get_last_xxl_client, XxlClient, XxlClientBuilder};
-use xxljob_sdk_rs::{AsyncJobHandler, JobContext, JobHandler, SyncJobHandler};
+use xxljob_sdk_rs::XxlClientBuilder;
+use xxljob_sdk_rs::{AsyncJobHandler, JobContext, JobHandler, JobOutcome};
 
 pub struct DemoJobHandler;
 
 #[async_trait]
 impl AsyncJobHandler for DemoJobHandler {
-    async fn process(&self, context: JobContext) -> anyhow::Result<JobContext> {
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
         log::info!(
             "async|DemoJobHandler job info; job_id:{}, log_id:{}, job_param:{:?}",
             &context.job_id,
@@ -15,10 +15,9 @@ impl AsyncJobHandler for DemoJobHandler {
             &context.job_param
         );
         let sleep_count = context
-            .job_param
-            .clone()
-            .unwrap_or_default()
-            .parse::<u64>()
+            .param_as::<Option<u64>>()
+            .ok()
+            .flatten()
             .unwrap_or(1);
         tokio::time::sleep(std::time::Duration::from_millis(sleep_count * 100)).await;
         log::info!(
@@ -26,7 +25,7 @@ impl AsyncJobHandler for DemoJobHandler {
             &context.job_id,
             &context.log_id
         );
-        Ok(context)
+        Ok(JobOutcome::success())
     }
 }
 
@@ -47,7 +46,7 @@ async fn main() -> anyhow::Result<()> {
         .parse()
         .unwrap_or(2000);
     let mut clients = vec![];
-    for i in 0..client_count {
+    for _ in 0..client_count {
         let client = XxlClientBuilder::new(admin_url.clone())
             .set_access_token("default_token".to_string())
             .set_log_path("xxl-rs-logs".to_string())
@@ -1,13 +1,13 @@
 use async_trait::async_trait;
 use std::sync::Arc;
-use xxljob_sdk_rs::{get_last_xxl_client, XxlClientBuilder};
-use xxljob_sdk_rs::{AsyncJobHandler, JobContext, JobHandler, SyncJobHandler};
+use xxljob_sdk_rs::{get_last_xxl_client, xxl_log, XxlClientBuilder};
+use xxljob_sdk_rs::{AsyncJobHandler, JobContext, JobHandler, JobOutcome, SyncJobHandler};
 
 pub struct DemoJobHandler;
 
 #[async_trait]
 impl AsyncJobHandler for DemoJobHandler {
-    async fn process(&self, context: JobContext) -> anyhow::Result<JobContext> {
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
         log::info!(
             "async|DemoJobHandler job info; job_id:{}, log_id:{}, job_param:{:?}",
             &context.job_id,
@@ -25,17 +25,18 @@ impl AsyncJobHandler for DemoJobHandler {
         }
          */
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        xxl_log!(context, "async|DemoJobHandler job process done");
         log::info!(
             "async|DemoJobHandler job process done; job_id:{}, log_id:{}",
             &context.job_id,
             &context.log_id
         );
-        Ok(context)
+        Ok(JobOutcome::success())
     }
 }
 
 impl SyncJobHandler for DemoJobHandler {
-    fn process(&self, context: JobContext) -> anyhow::Result<JobContext> {
+    fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
         log::info!(
             "sync|DemoJobHandler job info; job_id:{}, log_id:{}, job_param:{:?}",
             &context.job_id,
@@ -55,7 +56,7 @@ impl SyncJobHandler for DemoJobHandler {
             &context.job_id,
             &context.log_id
         );
-        Ok(context)
+        Ok(JobOutcome::success())
     }
 }
 
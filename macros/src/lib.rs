@@ -0,0 +1,133 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, FnArg, Ident, ItemFn, LitStr, Path, Token};
+
+/// `#[xxl_job]`的参数：任务处理器名称，以及可选的`crate = path`
+struct XxlJobArgs {
+    name: LitStr,
+    krate: Path,
+}
+
+impl Parse for XxlJobArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: LitStr = input.parse()?;
+        let mut krate: Path = syn::parse_quote!(::xxljob_sdk_rs);
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            krate = input.parse()?;
+            input.parse::<Option<Token![,]>>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("unexpected argument, expected `crate = path`"));
+        }
+        Ok(Self { name, krate })
+    }
+}
+
+/// 声明任务处理器；
+/// 用于`async fn(JobContext) -> anyhow::Result<T>`或`fn(JobContext) -> anyhow::Result<T>`，T为`()`或`JobOutcome`，
+/// 异步函数生成`AsyncJobHandler`，普通函数生成`SyncJobHandler`；
+/// 开启`macros` feature后，`XxlClientBuilder::build`时自动注册；
+///
+/// 通过其它crate重新导出使用时，用`crate = path`指定xxljob_sdk_rs的路径；
+///
+/// ```ignore
+/// #[xxl_job("demoJobHandler")]
+/// async fn demo_job(context: JobContext) -> anyhow::Result<()> {
+///     Ok(())
+/// }
+///
+/// #[xxl_job("otherJobHandler", crate = my_common::xxljob)]
+/// fn other_job(context: JobContext) -> anyhow::Result<()> {
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn xxl_job(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as XxlJobArgs);
+    let func = parse_macro_input!(item as ItemFn);
+    match expand(args, func) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(args: XxlJobArgs, func: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let XxlJobArgs { name, krate } = args;
+    if name.value().is_empty() {
+        return Err(syn::Error::new(name.span(), "job handler name is empty"));
+    }
+    let sig = &func.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "job handler function can not be generic",
+        ));
+    }
+    if sig.inputs.len() != 1 || matches!(sig.inputs.first(), Some(FnArg::Receiver(_))) {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "job handler function must take exactly one JobContext argument",
+        ));
+    }
+    let func_name = &sig.ident;
+    let handler_name = Ident::new(
+        &format!("__xxl_job_handler_{}", func_name),
+        Span::call_site(),
+    );
+    let handler_impl = if sig.asyncness.is_some() {
+        quote! {
+            #[#krate::__private::async_trait]
+            impl #krate::AsyncJobHandler for #handler_name {
+                async fn process(
+                    &self,
+                    context: #krate::JobContext,
+                ) -> #krate::__private::anyhow::Result<#krate::JobOutcome> {
+                    #func_name(context)
+                        .await
+                        .map(::core::convert::Into::into)
+                        .map_err(::core::convert::Into::into)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #krate::SyncJobHandler for #handler_name {
+                fn process(
+                    &self,
+                    context: #krate::JobContext,
+                ) -> #krate::__private::anyhow::Result<#krate::JobOutcome> {
+                    #func_name(context)
+                        .map(::core::convert::Into::into)
+                        .map_err(::core::convert::Into::into)
+                }
+            }
+        }
+    };
+    let handler_variant = if sig.asyncness.is_some() {
+        quote!(Async)
+    } else {
+        quote!(Sync)
+    };
+    Ok(quote! {
+        #func
+
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #handler_name;
+
+        #handler_impl
+
+        #krate::__private::inventory::submit! {
+            #krate::JobHandlerRegistration {
+                name: #name,
+                handler: || #krate::JobHandler::#handler_variant(
+                    ::std::sync::Arc::new(#handler_name)
+                ),
+            }
+        }
+    })
+}
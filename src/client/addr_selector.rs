@@ -0,0 +1,246 @@
+use crate::common::client_state::AdminAddrStatus;
+use crate::common::model::enum_type::AdminAddrSelectStrategy;
+use rand::Rng;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Default)]
+struct AddrHealth {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct SelectorState {
+    health: Vec<AddrHealth>,
+    next_index: usize,
+}
+
+/// 服务端地址选择器；
+/// 记录每个地址的健康状态，连续失败达到阈值后熔断，熔断期内跳过该地址，熔断结束后重新探测；
+#[derive(Debug)]
+pub struct AdminAddrSelector {
+    addrs: Vec<String>,
+    strategy: AdminAddrSelectStrategy,
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<SelectorState>,
+}
+
+impl AdminAddrSelector {
+    pub fn new(
+        addrs: Vec<String>,
+        strategy: AdminAddrSelectStrategy,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> Self {
+        let state = SelectorState {
+            health: vec![AddrHealth::default(); addrs.len()],
+            next_index: 0,
+        };
+        Self {
+            addrs,
+            strategy,
+            failure_threshold,
+            open_duration,
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn addrs(&self) -> &Vec<String> {
+        &self.addrs
+    }
+
+    /// 本次请求尝试的地址顺序；
+    /// 熔断中的地址不参与，如果所有地址都在熔断中，按熔断结束时间先后全部尝试；
+    pub fn select_order(&self) -> Vec<usize> {
+        let len = self.addrs.len();
+        let mut state = match self.state.lock() {
+            Ok(v) => v,
+            Err(_) => return (0..len).collect(),
+        };
+        let now = Instant::now();
+        let start = match self.strategy {
+            AdminAddrSelectStrategy::RoundRobin => {
+                let start = state.next_index % len.max(1);
+                state.next_index = start + 1;
+                start
+            }
+            AdminAddrSelectStrategy::StickyUntilFailure => state.next_index % len.max(1),
+            AdminAddrSelectStrategy::Random => rand::thread_rng().gen_range(0..len.max(1)),
+        };
+        let order: Vec<usize> = (0..len).map(|i| (start + i) % len).collect();
+        let available: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&i| state.health[i].open_until.is_none_or(|v| v <= now))
+            .collect();
+        if !available.is_empty() {
+            return available;
+        }
+        let mut all = order;
+        all.sort_by_key(|&i| state.health[i].open_until);
+        all
+    }
+
+    pub fn addr_status(&self) -> Vec<AdminAddrStatus> {
+        let state = match self.state.lock() {
+            Ok(v) => v,
+            Err(_) => return vec![],
+        };
+        let now = Instant::now();
+        self.addrs
+            .iter()
+            .zip(state.health.iter())
+            .map(|(addr, health)| AdminAddrStatus {
+                addr: addr.clone(),
+                consecutive_failures: health.consecutive_failures,
+                circuit_open: health.open_until.is_some_and(|v| v > now),
+                last_error: health.last_error.clone(),
+            })
+            .collect()
+    }
+
+    pub fn record_success(&self, index: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            let health = &mut state.health[index];
+            health.consecutive_failures = 0;
+            health.open_until = None;
+            health.last_error = None;
+            if self.strategy == AdminAddrSelectStrategy::StickyUntilFailure {
+                state.next_index = index;
+            }
+        }
+    }
+
+    pub fn record_failure(&self, index: usize, error: String) {
+        if let Ok(mut state) = self.state.lock() {
+            let health = &mut state.health[index];
+            health.consecutive_failures += 1;
+            health.last_error = Some(error);
+            if health.consecutive_failures >= self.failure_threshold {
+                health.open_until = Some(Instant::now() + self.open_duration);
+            }
+            if self.strategy == AdminAddrSelectStrategy::StickyUntilFailure
+                && state.next_index % self.addrs.len() == index
+            {
+                state.next_index = index + 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selector(
+        strategy: AdminAddrSelectStrategy,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> AdminAddrSelector {
+        AdminAddrSelector::new(
+            vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+            strategy,
+            failure_threshold,
+            open_duration,
+        )
+    }
+
+    #[test]
+    fn sticky_until_failure() {
+        let selector = selector(
+            AdminAddrSelectStrategy::StickyUntilFailure,
+            3,
+            Duration::from_secs(30),
+        );
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+        //失败后切换到下一个地址，成功后一直使用该地址
+        selector.record_failure(0, "error".to_owned());
+        selector.record_success(1);
+        assert_eq!(selector.select_order(), vec![1, 2, 0]);
+        assert_eq!(selector.select_order(), vec![1, 2, 0]);
+        //不是当前地址的失败不影响选择
+        selector.record_failure(2, "error".to_owned());
+        assert_eq!(selector.select_order(), vec![1, 2, 0]);
+        selector.record_failure(1, "error".to_owned());
+        assert_eq!(selector.select_order(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn round_robin_start_index() {
+        let selector = selector(
+            AdminAddrSelectStrategy::RoundRobin,
+            3,
+            Duration::from_secs(30),
+        );
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+        assert_eq!(selector.select_order(), vec![1, 2, 0]);
+        assert_eq!(selector.select_order(), vec![2, 0, 1]);
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn skip_open_circuit() {
+        let selector = selector(
+            AdminAddrSelectStrategy::RoundRobin,
+            2,
+            Duration::from_secs(30),
+        );
+        selector.record_failure(1, "error-1".to_owned());
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+        //连续失败达到阈值后熔断
+        selector.record_failure(1, "error-2".to_owned());
+        assert_eq!(selector.select_order(), vec![2, 0]);
+        assert_eq!(selector.select_order(), vec![2, 0]);
+        let status = selector.addr_status();
+        assert!(status[1].circuit_open);
+        assert_eq!(status[1].consecutive_failures, 2);
+        assert_eq!(status[1].last_error.as_deref(), Some("error-2"));
+        assert!(!status[0].circuit_open);
+
+        //成功后关闭熔断并清空错误
+        selector.record_success(1);
+        let status = selector.addr_status();
+        assert!(!status[1].circuit_open);
+        assert_eq!(status[1].consecutive_failures, 0);
+        assert_eq!(status[1].last_error, None);
+        assert_eq!(selector.select_order(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn reprobe_after_open_duration() {
+        let selector = selector(
+            AdminAddrSelectStrategy::StickyUntilFailure,
+            1,
+            Duration::from_millis(50),
+        );
+        selector.record_failure(0, "error".to_owned());
+        assert_eq!(selector.select_order(), vec![1, 2]);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(selector.select_order(), vec![1, 2, 0]);
+        assert!(!selector.addr_status()[0].circuit_open);
+        //重新探测仍失败时再次熔断
+        selector.record_failure(0, "error".to_owned());
+        assert_eq!(selector.select_order(), vec![1, 2]);
+    }
+
+    #[test]
+    fn all_open_fallback_order() {
+        let selector = selector(
+            AdminAddrSelectStrategy::RoundRobin,
+            1,
+            Duration::from_secs(30),
+        );
+        selector.record_failure(2, "error".to_owned());
+        std::thread::sleep(Duration::from_millis(2));
+        selector.record_failure(0, "error".to_owned());
+        std::thread::sleep(Duration::from_millis(2));
+        selector.record_failure(1, "error".to_owned());
+        //所有地址都在熔断中时，按熔断结束时间先后全部尝试
+        assert_eq!(selector.select_order(), vec![2, 0, 1]);
+        assert_eq!(selector.select_order(), vec![2, 0, 1]);
+    }
+}
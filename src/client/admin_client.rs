@@ -1,36 +1,52 @@
+use crate::client::addr_selector::AdminAddrSelector;
 use crate::common::client_config::ClientConfig;
+use crate::common::client_state::{AdminAddrStatus, ClientState};
 use crate::common::http_utils::{HttpUtils, ResponseWrap};
 use crate::common::model::admin_request::{CallbackParam, RegistryParam};
 use crate::common::model::XxlApiResult;
 use crate::common::{constant, get_app_version};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct AdminClient {
     client_config: Arc<ClientConfig>,
     client: reqwest::Client,
-    addrs: Vec<String>,
+    addr_selector: Arc<AdminAddrSelector>,
     headers: HashMap<String, String>,
+    client_state: Arc<ClientState>,
 }
 
 impl AdminClient {
-    pub fn new(client_config: Arc<ClientConfig>) -> anyhow::Result<Self> {
+    pub fn new(
+        client_config: Arc<ClientConfig>,
+        client_state: Arc<ClientState>,
+    ) -> anyhow::Result<Self> {
         let addrs_str = client_config.server_address.as_str();
         if addrs_str.is_empty() {
             return Err(anyhow::anyhow!("empty admin service address"));
         }
-        let addrs = addrs_str
+        let addrs: Vec<String> = addrs_str
             .split(",")
             .filter(|&v| !v.is_empty())
             .map(|v| v.to_owned())
             .collect();
+        let addr_selector = Arc::new(AdminAddrSelector::new(
+            addrs,
+            client_config.admin_addr_select_strategy.clone(),
+            client_config.admin_failure_threshold,
+            client_config.admin_circuit_open_duration,
+        ));
         let mut client_builder = reqwest::ClientBuilder::new();
         #[cfg(feature = "ssl_mode")]
         if client_config.ssl_danger_accept_invalid_certs {
             client_builder = client_builder.danger_accept_invalid_certs(true);
         }
-        client_builder = client_builder.timeout(std::time::Duration::from_millis(3000));
+        client_builder = client_builder.timeout(client_config.admin_request_timeout);
+        if let Some(connect_timeout) = client_config.admin_connect_timeout {
+            client_builder = client_builder.connect_timeout(connect_timeout);
+        }
         let client = client_builder.build()?;
         let mut headers = HashMap::new();
         if !client_config.access_token.is_empty() {
@@ -49,12 +65,17 @@ impl AdminClient {
         }
         Ok(Self {
             client,
-            addrs,
+            addr_selector,
             client_config,
             headers,
+            client_state,
         })
     }
 
+    pub fn addr_status(&self) -> Vec<AdminAddrStatus> {
+        self.addr_selector.addr_status()
+    }
+
     pub async fn registry(&self) -> anyhow::Result<()> {
         let address = format!(
             "http://{}:{}",
@@ -115,30 +136,54 @@ impl AdminClient {
         }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "xxl_job_admin_request",
+            skip(self, body),
+            fields(addr = tracing::field::Empty),
+            err
+        )
+    )]
     async fn request(&self, body: Vec<u8>, sub_url: &str) -> anyhow::Result<()> {
         let mut registry_success = false;
-        for addr in &self.addrs {
+        for index in self.addr_selector.select_order() {
+            let addr = &self.addr_selector.addrs()[index];
             let url = format!("{}/api/{}", addr, &sub_url);
-            match HttpUtils::request(
+            let start = Instant::now();
+            let res = match HttpUtils::request(
                 &self.client,
                 "POST",
                 &url,
                 body.clone(),
                 Some(&self.headers),
-                Some(3000),
+                Some(self.client_config.admin_request_timeout.as_millis() as u64),
             )
             .await
             {
-                Ok(resp) => {
-                    if let Ok(v) = Self::convert(&resp) {
-                        if v.is_success() {
-                            registry_success = true;
-                            break;
-                        }
-                    }
+                Ok(resp) => match Self::convert(&resp) {
+                    Ok(v) if v.is_success() => Ok(()),
+                    Ok(v) => Err(format!("code:{},msg:{}", v.code, v.msg.unwrap_or_default())),
+                    Err(err) => Err(err.to_string()),
+                },
+                Err(err) => Err(err.to_string()),
+            };
+            if sub_url == "registry" {
+                self.client_state
+                    .metrics()
+                    .record_heartbeat(addr, res.is_ok(), start.elapsed());
+            }
+            match res {
+                Ok(_) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::Span::current().record("addr", addr.as_str());
+                    self.addr_selector.record_success(index);
+                    registry_success = true;
+                    break;
                 }
                 Err(err) => {
-                    log::error!("call response error:{},url:{}", err, &url);
+                    log::error!("call response error:{},url:{}", &err, &url);
+                    self.addr_selector.record_failure(index, err);
                 }
             }
         }
@@ -1,8 +1,12 @@
 use crate::client::client::{set_last_xxl_client, XxlClient};
 use crate::common::actor_utils::create_actor_at_thread;
 use crate::common::client_config::ClientConfig;
+use crate::common::client_state::ClientState;
 use crate::common::ip_utils::{get_available_port, get_local_ip};
+use crate::common::model::enum_type::AdminAddrSelectStrategy;
+use crate::common::model::interceptor::JobInterceptor;
 use crate::common::share_data::ShareData;
+use crate::common::worker_pool::WorkerPoolConfig;
 use crate::executor::admin_server::ServerAccessActor;
 use crate::executor::core::ExecutorActor;
 use crate::server::web_server::ServerRunner;
@@ -11,6 +15,7 @@ use actix_rt::System;
 use bean_factory::{BeanDefinition, BeanFactory};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Debug, Default)]
 pub struct XxlClientBuilder {
@@ -23,6 +28,26 @@ pub struct XxlClientBuilder {
     log_retention_days: Option<u32>,
     ssl_danger_accept_invalid_certs: Option<bool>,
     extra_headers: HashMap<String, String>,
+    sync_pool_max_threads: Option<usize>,
+    sync_pool_queue_size: Option<usize>,
+    sync_pool_thread_name: Option<String>,
+    shutdown_on_sigterm: Option<Duration>,
+    interceptors: Vec<Arc<dyn JobInterceptor>>,
+    metrics_enabled: Option<bool>,
+    metrics_check_token: Option<bool>,
+    #[cfg(feature = "macros")]
+    auto_register_handlers: Option<bool>,
+    callback_batch_size: Option<usize>,
+    callback_batch_window: Option<Duration>,
+    admin_addr_select_strategy: Option<AdminAddrSelectStrategy>,
+    admin_failure_threshold: Option<u32>,
+    admin_circuit_open_duration: Option<Duration>,
+    admin_request_timeout: Option<Duration>,
+    admin_connect_timeout: Option<Duration>,
+    registry_heartbeat_interval: Option<Duration>,
+    registry_jitter: Option<Duration>,
+    callback_retry_delays: Option<Vec<Duration>>,
+    callback_log_retry_interval: Option<Duration>,
 }
 
 impl XxlClientBuilder {
@@ -58,6 +83,8 @@ impl XxlClientBuilder {
         self
     }
 
+    /// 执行日志保留天数，每天清理一次过期日志；
+    /// 过期的日期目录({log_path}/{yyyy-MM-dd})直接删除，不做压缩归档；小于3天时不清理；
     pub fn set_log_retention_days(mut self, log_retention_days: u32) -> Self {
         self.log_retention_days = Some(log_retention_days);
         self
@@ -81,12 +108,131 @@ impl XxlClientBuilder {
         self
     }
 
+    /// 同步任务线程池最大线程数，默认200
+    pub fn set_sync_pool_max_threads(mut self, max_threads: usize) -> Self {
+        self.sync_pool_max_threads = Some(max_threads);
+        self
+    }
+
+    /// 同步任务线程池排队上限，线程都在运行且排队已满时任务直接失败，默认1000
+    pub fn set_sync_pool_queue_size(mut self, queue_size: usize) -> Self {
+        self.sync_pool_queue_size = Some(queue_size);
+        self
+    }
+
+    /// 同步任务线程名前缀，默认xxljob-sync-worker
+    pub fn set_sync_pool_thread_name(mut self, thread_name: String) -> Self {
+        self.sync_pool_thread_name = Some(thread_name);
+        self
+    }
+
+    /// 每次回调服务端最多携带的任务结果数量，默认100
+    pub fn set_callback_batch_size(mut self, callback_batch_size: usize) -> Self {
+        self.callback_batch_size = Some(callback_batch_size);
+        self
+    }
+
+    /// 任务结果攒批回调的时间窗口，默认100毫秒
+    pub fn set_callback_batch_window(mut self, callback_batch_window: Duration) -> Self {
+        self.callback_batch_window = Some(callback_batch_window);
+        self
+    }
+
+    /// 多个服务端地址的选择策略，默认一直使用同一个地址，失败后切换
+    pub fn set_admin_addr_select_strategy(mut self, strategy: AdminAddrSelectStrategy) -> Self {
+        self.admin_addr_select_strategy = Some(strategy);
+        self
+    }
+
+    /// 服务端地址熔断设置：连续失败failure_threshold次后，open_duration内不再请求该地址，之后重新探测；
+    /// 默认连续失败3次熔断30秒；
+    pub fn set_admin_circuit_breaker(
+        mut self,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> Self {
+        self.admin_failure_threshold = Some(failure_threshold);
+        self.admin_circuit_open_duration = Some(open_duration);
+        self
+    }
+
+    /// 请求服务端的超时时间，默认3秒
+    pub fn set_admin_request_timeout(mut self, timeout: Duration) -> Self {
+        self.admin_request_timeout = Some(timeout);
+        self
+    }
+
+    /// 连接服务端的超时时间，默认只受请求超时时间限制
+    pub fn set_admin_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.admin_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// 注册心跳间隔，默认29.5秒
+    pub fn set_registry_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.registry_heartbeat_interval = Some(interval);
+        self
+    }
+
+    /// 首次注册与每次心跳增加[0, jitter)的随机延迟，避免大量执行器同时重启时集中注册；默认不延迟
+    pub fn set_registry_jitter(mut self, jitter: Duration) -> Self {
+        self.registry_jitter = Some(jitter);
+        self
+    }
+
+    /// 没有设置log_path时，回调失败后在内存中按该间隔依次重试；
+    /// 默认重试10次，间隔从4秒逐步增加到121秒；
+    pub fn set_callback_retry_delays(mut self, delays: Vec<Duration>) -> Self {
+        self.callback_retry_delays = Some(delays);
+        self
+    }
+
+    /// 设置log_path时，重试本地保存的回调的间隔，默认30秒
+    pub fn set_callback_log_retry_interval(mut self, interval: Duration) -> Self {
+        self.callback_log_retry_interval = Some(interval);
+        self
+    }
+
+    /// 添加全局任务拦截器，作用于所有任务处理器，按添加顺序调用
+    pub fn add_interceptor(mut self, interceptor: Arc<dyn JobInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// 在内嵌web服务开启`/metrics`指标接口(Prometheus文本格式)，默认关闭
+    pub fn set_metrics_enabled(mut self, metrics_enabled: bool) -> Self {
+        self.metrics_enabled = Some(metrics_enabled);
+        self
+    }
+
+    /// `/metrics`是否校验access_token，默认校验；不校验时抓取请求不需要带`XXL-JOB-ACCESS-TOKEN`
+    pub fn set_metrics_check_token(mut self, metrics_check_token: bool) -> Self {
+        self.metrics_check_token = Some(metrics_check_token);
+        self
+    }
+
+    /// 收到SIGTERM（非unix系统为Ctrl-C）时自动调用`XxlClient::shutdown`停止客户端；
+    /// timeout为等待运行中任务结束的最长时间；
+    /// 不会退出进程，应用可以通过`XxlClient::wait_shutdown`等待停止完成后再执行自己的清理与退出；
+    pub fn set_shutdown_on_sigterm(mut self, timeout: Duration) -> Self {
+        self.shutdown_on_sigterm = Some(timeout);
+        self
+    }
+
+    /// 构建客户端时是否自动注册通过`#[xxl_job]`声明的任务处理器，默认注册
+    #[cfg(feature = "macros")]
+    pub fn set_auto_register_handlers(mut self, auto_register_handlers: bool) -> Self {
+        self.auto_register_handlers = Some(auto_register_handlers);
+        self
+    }
+
     pub fn build(self) -> anyhow::Result<Arc<XxlClient>> {
         let start_port = 9900;
         let port = Self::get_port(start_port, self.port);
         if port == 0 {
             return Err(anyhow::anyhow!("no available port"));
         }
+        let default_pool_config = WorkerPoolConfig::default();
         let client_config = Arc::new(ClientConfig {
             server_address: Arc::new(self.server_address),
             access_token: Arc::new(self.access_token.unwrap_or_default()),
@@ -97,12 +243,65 @@ impl XxlClientBuilder {
             log_retention_days: self.log_retention_days.unwrap_or_default(),
             ssl_danger_accept_invalid_certs: self.ssl_danger_accept_invalid_certs.unwrap_or(true),
             extra_headers: Arc::new(self.extra_headers),
+            sync_pool_config: WorkerPoolConfig {
+                max_threads: self
+                    .sync_pool_max_threads
+                    .unwrap_or(default_pool_config.max_threads),
+                queue_size: self
+                    .sync_pool_queue_size
+                    .unwrap_or(default_pool_config.queue_size),
+                thread_name: self
+                    .sync_pool_thread_name
+                    .map(Arc::new)
+                    .unwrap_or(default_pool_config.thread_name),
+                keep_alive: default_pool_config.keep_alive,
+            },
+            callback_batch_size: self.callback_batch_size.unwrap_or(100).max(1),
+            callback_batch_window: self
+                .callback_batch_window
+                .unwrap_or(Duration::from_millis(100)),
+            admin_addr_select_strategy: self.admin_addr_select_strategy.unwrap_or_default(),
+            admin_failure_threshold: self.admin_failure_threshold.unwrap_or(3).max(1),
+            admin_circuit_open_duration: self
+                .admin_circuit_open_duration
+                .unwrap_or(Duration::from_secs(30)),
+            admin_request_timeout: self
+                .admin_request_timeout
+                .unwrap_or(Duration::from_millis(3000)),
+            admin_connect_timeout: self.admin_connect_timeout,
+            registry_heartbeat_interval: self
+                .registry_heartbeat_interval
+                .unwrap_or(Duration::from_millis(29500)),
+            registry_jitter: self.registry_jitter.unwrap_or_default(),
+            callback_retry_delays: Arc::new(
+                self.callback_retry_delays
+                    .unwrap_or_else(|| (2u64..12u64).map(|i| Duration::from_secs(i * i)).collect()),
+            ),
+            callback_log_retry_interval: self
+                .callback_log_retry_interval
+                .unwrap_or(Duration::from_secs(30)),
+            interceptors: self.interceptors,
+            metrics_enabled: self.metrics_enabled.unwrap_or(false),
+            metrics_check_token: self.metrics_check_token.unwrap_or(true),
         });
         if client_config.access_token.is_empty() {
             log::warn!("api access_token is empty!");
         }
         let client = build_client(client_config)?;
+        #[cfg(feature = "macros")]
+        if self.auto_register_handlers.unwrap_or(true) {
+            for registration in inventory::iter::<crate::JobHandlerRegistration> {
+                log::info!("auto register job handler:{}", registration.name);
+                client.register(
+                    Arc::new(registration.name.to_owned()),
+                    (registration.handler)(),
+                )?;
+            }
+        }
         set_last_xxl_client(client.clone());
+        if let Some(timeout) = self.shutdown_on_sigterm {
+            shutdown_on_sigterm(client.clone(), timeout);
+        }
         Ok(client)
     }
 
@@ -133,19 +332,62 @@ fn build_client(client_config: Arc<ClientConfig>) -> anyhow::Result<Arc<XxlClien
     rx.recv()?
 }
 
+fn shutdown_on_sigterm(client: Arc<XxlClient>, timeout: Duration) {
+    //停止客户端时会停止actor所在线程，信号处理放在单独的线程运行
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("shutdown signal listener start error:{}", e);
+                return;
+            }
+        };
+        rt.block_on(async move {
+            if let Err(e) = wait_terminate_signal().await {
+                log::error!("shutdown signal listener error:{}", e);
+                return;
+            }
+            log::info!("receive terminate signal, shutdown xxl client");
+            if let Err(e) = client.shutdown(timeout).await {
+                log::error!("shutdown xxl client error:{}", e);
+            }
+        });
+    });
+}
+
+#[cfg(unix)]
+async fn wait_terminate_signal() -> std::io::Result<()> {
+    let mut signal = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    signal.recv().await;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn wait_terminate_signal() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await
+}
+
 fn init_factory(client_config: Arc<ClientConfig>) -> anyhow::Result<BeanFactory> {
     let factory = BeanFactory::new();
+    let client_state = Arc::new(ClientState::default());
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         //ExecutorActor::new(client_config.clone()).start(),
-        create_actor_at_thread(ExecutorActor::new(client_config.clone())),
+        create_actor_at_thread(ExecutorActor::new(
+            client_config.clone(),
+            client_state.clone(),
+        )),
     ));
     factory.register(BeanDefinition::actor_with_inject_from_obj(
-        ServerRunner {}.start(),
+        ServerRunner::default().start(),
     ));
     factory.register(BeanDefinition::actor_with_inject_from_obj(
-        ServerAccessActor::new(client_config.clone()).start(),
+        ServerAccessActor::new(client_config.clone(), client_state.clone()).start(),
     ));
     factory.register(BeanDefinition::from_obj(client_config.clone()));
+    factory.register(BeanDefinition::from_obj(client_state));
     Ok(factory)
 }
 
@@ -155,7 +397,9 @@ async fn async_init(client_config: Arc<ClientConfig>) -> anyhow::Result<Arc<XxlC
     let share_data = Arc::new(ShareData {
         executor_actor: factory_data.get_actor().unwrap(),
         server_access_actor: factory_data.get_actor().unwrap(),
+        server_runner: factory_data.get_actor().unwrap(),
         client_config,
+        client_state: factory_data.get_bean().unwrap(),
     });
     let client = XxlClient::new(share_data.clone());
     /*
@@ -1,7 +1,18 @@
-use crate::common::model::handler::{AsyncJobHandler, JobHandler, JobHandlerValue, SyncJobHandler};
+use crate::client::manage_client::AdminManageClient;
+use crate::common::client_state::{
+    CallbackBatchMetrics, ClientStatus, JobHandlerStatus, RegistryStatus,
+};
+use crate::common::model::handler::{
+    AsyncJobHandler, JobHandler, JobHandlerConfig, JobHandlerValue, SyncJobHandler,
+};
 use crate::common::share_data::ShareData;
-use crate::executor::model::ExecutorActorReq;
+use crate::executor::model::{
+    ExecutorActorReq, ExecutorActorResult, ServerAccessActorReq, ServerAccessActorResult,
+};
+use crate::server::web_server::ServerRunnerReq;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
 lazy_static::lazy_static! {
     static ref LAST_XXL_CLIENT: Mutex<Option<Arc<XxlClient>>> =  Mutex::new(None);
@@ -32,17 +43,184 @@ impl XxlClient {
         Arc::new(Self { share_data })
     }
 
-    /*
-    pub async fn stop(&self) -> anyhow::Result<()> {
+    /// 批量回调统计
+    pub fn callback_batch_metrics(&self) -> CallbackBatchMetrics {
+        self.share_data.client_state.get_callback_batch_metrics()
+    }
+
+    /// 客户端状态，包含注册状态、各服务端地址最近的错误、待反馈的任务结果数量与绑定地址
+    pub async fn status(&self) -> anyhow::Result<ClientStatus> {
+        let (pending_callbacks, admin_addrs) = match self
+            .share_data
+            .server_access_actor
+            .send(ServerAccessActorReq::QueryStatus)
+            .await??
+        {
+            ServerAccessActorResult::Status {
+                pending_callbacks,
+                admin_addrs,
+            } => (pending_callbacks, admin_addrs),
+            _ => (0, vec![]),
+        };
+        let client_config = &self.share_data.client_config;
+        Ok(ClientStatus {
+            registry: self.share_data.client_state.get_registry_status(),
+            admin_addrs,
+            pending_callbacks,
+            bind_address: client_config.get_http_addr(),
+            executor_address: format!("http://{}:{}", client_config.ip, client_config.port),
+            shutting_down: self.share_data.client_state.is_shutting_down(),
+        })
+    }
+
+    /// 订阅注册状态，每次注册心跳完成后更新；
+    /// 可以通过`changed()`等待变化，在状态变为`RegistryState::Lost`时告警或标记服务未就绪；
+    pub fn subscribe_registry_status(&self) -> watch::Receiver<RegistryStatus> {
+        self.share_data.client_state.subscribe_registry_status()
+    }
+
+    /// 创建服务端管理接口客户端，复用当前客户端的服务端地址、TLS设置与超时时间
+    pub fn admin_manage_client(
+        &self,
+        user_name: String,
+        password: String,
+    ) -> anyhow::Result<AdminManageClient> {
+        AdminManageClient::new(self.share_data.client_config.clone(), user_name, password)
+    }
+
+    /// 停止客户端；
+    /// 从服务端注销执行器并停止接收新任务，等待运行中与排队中的任务结束，
+    /// 超过timeout后终止仍在运行的任务；反馈完任务结果后停止内嵌web服务与actor线程；
+    pub async fn shutdown(&self, timeout: Duration) -> anyhow::Result<()> {
+        if !self.share_data.client_state.start_shutdown() {
+            return Ok(());
+        }
+        let res = self.do_shutdown(timeout).await;
         self.share_data
+            .client_state
+            .record_shutdown_result(res.as_ref().map(|_| ()).map_err(|e| e.to_string()));
+        res
+    }
+
+    /// 等待客户端停止完成，返回停止结果；
+    /// 配合`XxlClientBuilder::set_shutdown_on_sigterm`使用，返回后由应用执行自己的清理并决定是否退出进程；
+    pub async fn wait_shutdown(&self) -> anyhow::Result<()> {
+        let mut rx = self.share_data.client_state.subscribe_shutdown_result();
+        let res = rx
+            .wait_for(|v| v.is_some())
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .clone();
+        match res {
+            Some(Err(e)) => Err(anyhow::anyhow!(e)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn do_shutdown(&self, timeout: Duration) -> anyhow::Result<()> {
+        log::info!("xxl client shutdown start");
+        let deadline = Instant::now() + timeout;
+        if let Err(e) = self
+            .share_data
             .server_access_actor
-            .send(ServerAccessActorReq::Stop)
+            .send(ServerAccessActorReq::RegistryRemove)
+            .await?
+        {
+            log::warn!("shutdown registry remove error:{}", e);
+        }
+        while let ExecutorActorResult::JobRunning = self
+            .share_data
+            .executor_actor
+            .send(ExecutorActorReq::CheckIdle)
+            .await??
+        {
+            if Instant::now() >= deadline {
+                log::warn!("shutdown wait job timeout, kill running jobs");
+                self.share_data
+                    .executor_actor
+                    .send(ExecutorActorReq::KillAll {
+                        reason: "executor shutdown".to_owned(),
+                    })
+                    .await??;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        //被终止任务的结果还需要反馈，至少再等待1秒
+        let flush_deadline = deadline.max(Instant::now() + Duration::from_secs(1));
+        loop {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if let ServerAccessActorResult::PendingCallbacks(count) = self
+                .share_data
+                .server_access_actor
+                .send(ServerAccessActorReq::QueryPendingCallbacks)
+                .await??
+            {
+                if count == 0 {
+                    break;
+                }
+                if Instant::now() >= flush_deadline {
+                    log::warn!("shutdown flush callback timeout, pending:{}", count);
+                    break;
+                }
+            }
+        }
+        //停止后actor线程退出，不再等待返回结果
+        self.share_data
+            .server_runner
+            .send(ServerRunnerReq::Stop)
+            .await
+            .ok();
+        self.share_data
+            .executor_actor
+            .send(ExecutorActorReq::Stop)
+            .await
+            .ok();
+        log::info!("xxl client shutdown done");
+        Ok(())
+    }
+
+    /// 已注册的任务处理器，包含运行中与排队中的任务数量；GLUE脚本任务的名称为`glue:{job_id}`
+    pub async fn handlers(&self) -> anyhow::Result<Vec<JobHandlerStatus>> {
+        match self
+            .share_data
+            .executor_actor
+            .send(ExecutorActorReq::QueryHandlers)
+            .await??
+        {
+            ExecutorActorResult::Handlers(v) => Ok(v),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// 替换已注册的任务处理器，保留原来的配置；
+    /// 运行中的任务继续使用旧的任务处理器执行完，排队中与之后调度的任务使用新的任务处理器；
+    pub async fn replace(
+        &self,
+        job_name: Arc<String>,
+        job_handler: JobHandler,
+    ) -> anyhow::Result<()> {
+        self.share_data
+            .executor_actor
+            .send(ExecutorActorReq::Replace {
+                job_name,
+                job_handler,
+            })
             .await??;
         Ok(())
     }
-    */
 
-    /// 注册任务
+    /// 移除任务处理器；运行中的任务继续执行完，排队中的任务按失败反馈，之后调度的任务返回没有注册的错误；
+    /// 运行中的任务结束前仍可被服务端终止，执行器也不会判定为空闲；期间重新注册同名任务会等这些任务结束后才串行执行；
+    pub async fn unregister(&self, job_name: Arc<String>) -> anyhow::Result<()> {
+        self.share_data
+            .executor_actor
+            .send(ExecutorActorReq::Unregister { job_name })
+            .await??;
+        Ok(())
+    }
+
+    /// 注册任务；名称已注册时替换任务处理器与配置，运行中的任务继续使用旧的任务处理器
     pub fn register(&self, job_name: Arc<String>, job_handler: JobHandler) -> anyhow::Result<()> {
         self.share_data
             .executor_actor
@@ -53,6 +231,21 @@ impl XxlClient {
         Ok(())
     }
 
+    /// 注册任务，并设置任务的排队上限、排队溢出处理方式与强制阻塞处理策略
+    pub fn register_with_config(
+        &self,
+        job_name: Arc<String>,
+        job_handler: JobHandler,
+        config: JobHandlerConfig,
+    ) -> anyhow::Result<()> {
+        self.share_data
+            .executor_actor
+            .do_send(ExecutorActorReq::Register(
+                JobHandlerValue::new_with_config(job_name, job_handler, config),
+            ));
+        Ok(())
+    }
+
     /// 注册任务
     pub fn register_async(
         &self,
@@ -0,0 +1,324 @@
+use crate::common::client_config::ClientConfig;
+use crate::common::http_utils::{HttpUtils, ResponseWrap};
+use crate::common::model::api_model::LogResult;
+use crate::common::model::manage_model::{
+    JobGroupQuery, JobInfoQuery, JobLogQuery, PageResult, XxlJobGroup, XxlJobInfo, XxlJobLog,
+};
+use crate::common::model::XxlApiResult;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginParam<'a> {
+    user_name: &'a str,
+    password: &'a str,
+    if_remember: &'a str,
+}
+
+#[derive(Serialize)]
+struct IdParam<T> {
+    id: T,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TriggerParam<'a> {
+    id: u64,
+    executor_param: &'a str,
+    address_list: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogDetailParam {
+    log_id: u64,
+    from_line_num: i32,
+}
+
+/// 服务端(xxl-job-admin)管理接口客户端；
+/// 调用服务端页面使用的`/jobgroup/*`、`/jobinfo/*`、`/joblog/*`接口管理执行器、任务与调度日志，
+/// 首次请求前自动登录，登录失效后重新登录一次；
+pub struct AdminManageClient {
+    client_config: Arc<ClientConfig>,
+    client: reqwest::Client,
+    admin_address: String,
+    user_name: String,
+    password: String,
+    cookie: Mutex<Option<String>>,
+}
+
+impl AdminManageClient {
+    /// 使用client_config中的第一个服务端地址、TLS设置、超时时间与附加请求头
+    pub fn new(
+        client_config: Arc<ClientConfig>,
+        user_name: String,
+        password: String,
+    ) -> anyhow::Result<Self> {
+        let admin_address = client_config
+            .server_address
+            .split(",")
+            .find(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("empty admin service address"))?
+            .trim_end_matches('/')
+            .to_owned();
+        let mut client_builder = reqwest::ClientBuilder::new();
+        #[cfg(feature = "ssl_mode")]
+        if client_config.ssl_danger_accept_invalid_certs {
+            client_builder = client_builder.danger_accept_invalid_certs(true);
+        }
+        if !client_config.admin_request_timeout.is_zero() {
+            client_builder = client_builder.timeout(client_config.admin_request_timeout);
+        }
+        if let Some(connect_timeout) = client_config.admin_connect_timeout {
+            client_builder = client_builder.connect_timeout(connect_timeout);
+        }
+        let client = client_builder.build()?;
+        Ok(Self {
+            client_config,
+            client,
+            admin_address,
+            user_name,
+            password,
+            cookie: Mutex::new(None),
+        })
+    }
+
+    /// 登录并保存会话cookie
+    pub async fn login(&self) -> anyhow::Result<()> {
+        let param = LoginParam {
+            user_name: &self.user_name,
+            password: &self.password,
+            if_remember: "on",
+        };
+        let resp = self.request("/login", &param, None).await?;
+        let result: XxlApiResult<String> = serde_json::from_slice(&resp.body)?;
+        if !result.is_success() {
+            return Err(anyhow::anyhow!(
+                "admin login failed, code:{},msg:{}",
+                result.code,
+                result.msg.unwrap_or_default()
+            ));
+        }
+        let cookie = resp
+            .headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case("set-cookie"))
+            .filter_map(|(_, v)| v.split(';').next())
+            .collect::<Vec<&str>>()
+            .join("; ");
+        if cookie.is_empty() {
+            return Err(anyhow::anyhow!("admin login failed, no session cookie"));
+        }
+        if let Ok(mut v) = self.cookie.lock() {
+            *v = Some(cookie);
+        }
+        Ok(())
+    }
+
+    /// 分页查询执行器
+    pub async fn job_group_page_list(
+        &self,
+        query: &JobGroupQuery,
+    ) -> anyhow::Result<PageResult<XxlJobGroup>> {
+        self.post_form("/jobgroup/pageList", query).await
+    }
+
+    pub async fn job_group_load(&self, id: i32) -> anyhow::Result<XxlJobGroup> {
+        self.call("/jobgroup/loadById", &IdParam { id })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("job group not found, id:{}", id))
+    }
+
+    /// 新增执行器
+    pub async fn job_group_save(&self, group: &XxlJobGroup) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobgroup/save", group).await?;
+        Ok(())
+    }
+
+    pub async fn job_group_update(&self, group: &XxlJobGroup) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobgroup/update", group).await?;
+        Ok(())
+    }
+
+    pub async fn job_group_remove(&self, id: i32) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobgroup/remove", &IdParam { id })
+            .await?;
+        Ok(())
+    }
+
+    /// 分页查询任务
+    pub async fn job_info_page_list(
+        &self,
+        query: &JobInfoQuery,
+    ) -> anyhow::Result<PageResult<XxlJobInfo>> {
+        self.post_form("/jobinfo/pageList", query).await
+    }
+
+    /// 新增任务，返回任务id
+    pub async fn job_info_add(&self, job_info: &XxlJobInfo) -> anyhow::Result<u64> {
+        let id: Option<String> = self.call("/jobinfo/add", job_info).await?;
+        Ok(id.unwrap_or_default().parse()?)
+    }
+
+    pub async fn job_info_update(&self, job_info: &XxlJobInfo) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobinfo/update", job_info).await?;
+        Ok(())
+    }
+
+    pub async fn job_info_remove(&self, id: u64) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobinfo/remove", &IdParam { id })
+            .await?;
+        Ok(())
+    }
+
+    /// 启动任务调度
+    pub async fn job_info_start(&self, id: u64) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobinfo/start", &IdParam { id })
+            .await?;
+        Ok(())
+    }
+
+    /// 停止任务调度
+    pub async fn job_info_stop(&self, id: u64) -> anyhow::Result<()> {
+        self.call::<_, String>("/jobinfo/stop", &IdParam { id })
+            .await?;
+        Ok(())
+    }
+
+    /// 执行一次任务；address_list为空时按任务的路由策略选择执行器
+    pub async fn job_info_trigger(
+        &self,
+        id: u64,
+        executor_param: &str,
+        address_list: &str,
+    ) -> anyhow::Result<()> {
+        let param = TriggerParam {
+            id,
+            executor_param,
+            address_list,
+        };
+        self.call::<_, String>("/jobinfo/trigger", &param).await?;
+        Ok(())
+    }
+
+    /// 分页查询调度日志
+    pub async fn job_log_page_list(
+        &self,
+        query: &JobLogQuery,
+    ) -> anyhow::Result<PageResult<XxlJobLog>> {
+        self.post_form("/joblog/pageList", query).await
+    }
+
+    /// 查询任务执行日志
+    pub async fn job_log_detail(
+        &self,
+        log_id: u64,
+        from_line_num: i32,
+    ) -> anyhow::Result<LogResult> {
+        let param = LogDetailParam {
+            log_id,
+            from_line_num,
+        };
+        Ok(self
+            .call("/joblog/logDetailCat", &param)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// 终止运行中的任务
+    pub async fn job_log_kill(&self, log_id: u64) -> anyhow::Result<()> {
+        self.call::<_, String>("/joblog/logKill", &IdParam { id: log_id })
+            .await?;
+        Ok(())
+    }
+
+    async fn call<P, R>(&self, path: &str, param: &P) -> anyhow::Result<Option<R>>
+    where
+        P: Serialize,
+        R: DeserializeOwned + Default,
+    {
+        let result: XxlApiResult<R> = self.post_form(path, param).await?;
+        if result.is_success() {
+            Ok(result.content)
+        } else {
+            Err(anyhow::anyhow!(
+                "admin request {} failed, code:{},msg:{}",
+                path,
+                result.code,
+                result.msg.unwrap_or_default()
+            ))
+        }
+    }
+
+    async fn post_form<P, R>(&self, path: &str, param: &P) -> anyhow::Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let mut cookie = self.get_cookie();
+        if cookie.is_none() {
+            self.login().await?;
+            cookie = self.get_cookie();
+        }
+        let resp = self.request(path, param, cookie.as_deref()).await?;
+        if let Ok(v) = serde_json::from_slice(&resp.body) {
+            return Ok(v);
+        }
+        //登录失效时服务端跳转到登录页，重新登录后再请求一次
+        self.login().await?;
+        let cookie = self.get_cookie();
+        let resp = self.request(path, param, cookie.as_deref()).await?;
+        serde_json::from_slice(&resp.body).map_err(|e| {
+            anyhow::anyhow!(
+                "admin request {} response error:{},status:{}",
+                path,
+                e,
+                resp.status
+            )
+        })
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "xxl_job_admin_manage_request", skip(self, param, cookie))
+    )]
+    async fn request<P: Serialize>(
+        &self,
+        path: &str,
+        param: &P,
+        cookie: Option<&str>,
+    ) -> anyhow::Result<ResponseWrap> {
+        let mut headers: HashMap<String, String> =
+            self.client_config.extra_headers.as_ref().clone();
+        headers.insert(
+            "Content-Type".to_owned(),
+            "application/x-www-form-urlencoded".to_owned(),
+        );
+        if let Some(cookie) = cookie {
+            headers.insert("Cookie".to_owned(), cookie.to_owned());
+        }
+        let url = format!("{}{}", &self.admin_address, path);
+        let body = serde_urlencoded::to_string(param)?.into_bytes();
+        HttpUtils::request(&self.client, "POST", &url, body, Some(&headers), None).await
+    }
+
+    fn get_cookie(&self) -> Option<String> {
+        self.cookie.lock().ok().and_then(|v| v.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id_param_keep_sign() {
+        let body = serde_urlencoded::to_string(IdParam { id: -1i32 }).unwrap();
+        assert_eq!(body, "id=-1");
+        let body = serde_urlencoded::to_string(IdParam { id: u64::MAX }).unwrap();
+        assert_eq!(body, format!("id={}", u64::MAX));
+    }
+}
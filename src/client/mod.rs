@@ -1,5 +1,7 @@
+pub mod addr_selector;
 pub mod admin_client;
 pub mod builder;
+pub mod manage_client;
 
 #[allow(clippy::module_inception)]
 pub mod client;
@@ -1,5 +1,9 @@
+use crate::common::model::enum_type::AdminAddrSelectStrategy;
+use crate::common::model::interceptor::JobInterceptor;
+use crate::common::worker_pool::WorkerPoolConfig;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Debug, Default)]
 pub struct ClientConfig {
@@ -12,6 +16,29 @@ pub struct ClientConfig {
     pub log_retention_days: u32,
     pub ssl_danger_accept_invalid_certs: bool,
     pub extra_headers: Arc<HashMap<String, String>>,
+    pub sync_pool_config: WorkerPoolConfig,
+    pub callback_batch_size: usize,
+    pub callback_batch_window: Duration,
+    pub admin_addr_select_strategy: AdminAddrSelectStrategy,
+    /// 服务端地址连续失败多少次后熔断
+    pub admin_failure_threshold: u32,
+    /// 服务端地址熔断时长，熔断结束后重新探测
+    pub admin_circuit_open_duration: Duration,
+    pub admin_request_timeout: Duration,
+    pub admin_connect_timeout: Option<Duration>,
+    pub registry_heartbeat_interval: Duration,
+    /// 注册与心跳的随机延迟上限
+    pub registry_jitter: Duration,
+    /// 没有设置log_path时，回调失败后内存中重试的间隔
+    pub callback_retry_delays: Arc<Vec<Duration>>,
+    /// 重试本地保存的回调的间隔
+    pub callback_log_retry_interval: Duration,
+    /// 全局任务拦截器
+    pub interceptors: Vec<Arc<dyn JobInterceptor>>,
+    /// 是否开启`/metrics`指标接口
+    pub metrics_enabled: bool,
+    /// `/metrics`是否校验access_token
+    pub metrics_check_token: bool,
 }
 
 impl ClientConfig {
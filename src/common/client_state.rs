@@ -0,0 +1,166 @@
+use crate::common::metrics::ExecutorMetrics;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::watch;
+
+/// 客户端运行状态，在各个actor与api之间共享
+#[derive(Debug, Default)]
+pub struct ClientState {
+    shutting_down: AtomicBool,
+    /// 停止完成后的结果，停止前为None
+    shutdown_result: watch::Sender<Option<Result<(), String>>>,
+    callback_batch_count: AtomicU64,
+    callback_param_count: AtomicU64,
+    callback_last_batch_size: AtomicU64,
+    callback_max_batch_size: AtomicU64,
+    registry_status: watch::Sender<RegistryStatus>,
+    metrics: ExecutorMetrics,
+}
+
+/// 执行器在服务端的注册状态
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RegistryState {
+    /// 还没有注册成功过
+    #[default]
+    Unregistered,
+    Registered,
+    /// 注册成功后，心跳请求在所有服务端地址都失败
+    Lost,
+    /// 已从服务端注销
+    Removed,
+}
+
+/// 注册状态
+#[derive(Clone, Debug, Default)]
+pub struct RegistryStatus {
+    pub state: RegistryState,
+    /// 最近一次注册成功的时间
+    pub last_registry_time: Option<SystemTime>,
+    /// 最近一次注册失败的错误，注册成功后清空
+    pub last_error: Option<String>,
+}
+
+/// 服务端地址状态
+#[derive(Clone, Debug)]
+pub struct AdminAddrStatus {
+    pub addr: String,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 是否在熔断中
+    pub circuit_open: bool,
+    /// 最近一次请求失败的错误，请求成功后清空
+    pub last_error: Option<String>,
+}
+
+/// 客户端状态
+#[derive(Clone, Debug)]
+pub struct ClientStatus {
+    pub registry: RegistryStatus,
+    pub admin_addrs: Vec<AdminAddrStatus>,
+    /// 还没有反馈到服务端的任务结果数量（不包含保存在本地回调日志中的结果）
+    pub pending_callbacks: usize,
+    /// 内嵌web服务绑定的地址
+    pub bind_address: String,
+    /// 注册到服务端的执行器地址
+    pub executor_address: String,
+    pub shutting_down: bool,
+}
+
+/// 任务处理器状态
+#[derive(Clone, Debug)]
+pub struct JobHandlerStatus {
+    pub name: Arc<String>,
+    /// 运行中的任务数量
+    pub running_jobs: usize,
+    /// 排队中的任务数量
+    pub queued_jobs: usize,
+}
+
+/// 批量回调统计
+#[derive(Clone, Debug, Default)]
+pub struct CallbackBatchMetrics {
+    /// 回调请求次数
+    pub batch_count: u64,
+    /// 回调的任务结果数量
+    pub param_count: u64,
+    /// 最近一次回调的批量大小
+    pub last_batch_size: u64,
+    /// 最大的批量大小
+    pub max_batch_size: u64,
+}
+
+impl ClientState {
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// 标记开始停止，返回是否是第一次标记
+    pub fn start_shutdown(&self) -> bool {
+        !self.shutting_down.swap(true, Ordering::SeqCst)
+    }
+
+    /// 记录停止结果，通知等待停止的任务
+    pub fn record_shutdown_result(&self, result: Result<(), String>) {
+        self.shutdown_result.send_replace(Some(result));
+    }
+
+    pub fn subscribe_shutdown_result(&self) -> watch::Receiver<Option<Result<(), String>>> {
+        self.shutdown_result.subscribe()
+    }
+
+    pub fn record_callback_batch(&self, batch_size: usize) {
+        let batch_size = batch_size as u64;
+        self.callback_batch_count.fetch_add(1, Ordering::Relaxed);
+        self.callback_param_count
+            .fetch_add(batch_size, Ordering::Relaxed);
+        self.callback_last_batch_size
+            .store(batch_size, Ordering::Relaxed);
+        self.callback_max_batch_size
+            .fetch_max(batch_size, Ordering::Relaxed);
+    }
+
+    pub fn record_registry_success(&self) {
+        self.registry_status.send_modify(|status| {
+            status.state = RegistryState::Registered;
+            status.last_registry_time = Some(SystemTime::now());
+            status.last_error = None;
+        });
+    }
+
+    pub fn record_registry_failure(&self, error: String) {
+        self.registry_status.send_modify(|status| {
+            if status.state == RegistryState::Registered {
+                status.state = RegistryState::Lost;
+            }
+            status.last_error = Some(error);
+        });
+    }
+
+    pub fn record_registry_removed(&self) {
+        self.registry_status.send_modify(|status| {
+            status.state = RegistryState::Removed;
+        });
+    }
+
+    pub fn get_registry_status(&self) -> RegistryStatus {
+        self.registry_status.borrow().clone()
+    }
+
+    pub fn subscribe_registry_status(&self) -> watch::Receiver<RegistryStatus> {
+        self.registry_status.subscribe()
+    }
+
+    pub fn metrics(&self) -> &ExecutorMetrics {
+        &self.metrics
+    }
+
+    pub fn get_callback_batch_metrics(&self) -> CallbackBatchMetrics {
+        CallbackBatchMetrics {
+            batch_count: self.callback_batch_count.load(Ordering::Relaxed),
+            param_count: self.callback_param_count.load(Ordering::Relaxed),
+            last_batch_size: self.callback_last_batch_size.load(Ordering::Relaxed),
+            max_batch_size: self.callback_max_batch_size.load(Ordering::Relaxed),
+        }
+    }
+}
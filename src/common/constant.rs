@@ -3,3 +3,6 @@ use std::sync::Arc;
 lazy_static::lazy_static! {
     pub static ref EXECUTOR: Arc<String> =  Arc::new("EXECUTOR".to_string());
 }
+
+/// 执行日志最少保留天数，小于该值时不清理执行日志
+pub const MIN_LOG_RETENTION_DAYS: u32 = 3;
@@ -0,0 +1,462 @@
+use crate::common::constant::MIN_LOG_RETENTION_DAYS;
+use crate::common::model::api_model::LogResult;
+use chrono::{Local, NaiveDate, TimeZone};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::mpsc::Sender;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// 执行日志文件路径，与java执行器保持一致：{log_path}/{yyyy-MM-dd}/{log_id}.log；
+/// 没有设置log_path时不记录执行日志；
+pub fn make_log_file_name(log_path: &str, log_date_time: i64, log_id: u64) -> Option<String> {
+    if log_path.is_empty() {
+        return None;
+    }
+    let log_date = Local
+        .timestamp_millis_opt(log_date_time)
+        .single()
+        .unwrap_or_else(Local::now);
+    let path = PathBuf::from(log_path)
+        .join(log_date.format("%Y-%m-%d").to_string())
+        .join(format!("{}.log", log_id));
+    Some(path.to_string_lossy().into_owned())
+}
+
+/// GLUE脚本文件目录：{log_path}/gluesource；
+/// 没有设置log_path时使用系统临时目录；
+pub fn glue_src_path(log_path: &str) -> PathBuf {
+    if log_path.is_empty() {
+        std::env::temp_dir()
+            .join("xxljob-sdk-rs")
+            .join("gluesource")
+    } else {
+        PathBuf::from(log_path).join("gluesource")
+    }
+}
+
+/// 日志行格式：{time} [{source}]-[{line}]-[{thread}] {msg}
+pub fn format_log_line(location: Option<(&str, u32)>, msg: &str) -> String {
+    let thread = std::thread::current();
+    let thread_name = match thread.name() {
+        Some(v) => v.to_owned(),
+        None => format!("{:?}", thread.id()),
+    };
+    let now = Local::now().format("%Y-%m-%d %H:%M:%S");
+    match location {
+        Some((source, line)) => format!(
+            "{} [{}]-[{}]-[{}] {}\n",
+            now, source, line, thread_name, msg
+        ),
+        None => format!("{} [{}] {}\n", now, thread_name, msg),
+    }
+}
+
+fn open_log_file(log_file_name: &str) -> std::io::Result<File> {
+    let path = Path::new(log_file_name);
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+pub fn append_log(log_file_name: &str, content: &str) -> anyhow::Result<()> {
+    append_log_bytes(log_file_name, content.as_bytes())
+}
+
+fn append_log_bytes(log_file_name: &str, content: &[u8]) -> anyhow::Result<()> {
+    let mut file = open_log_file(log_file_name)?;
+    file.write_all(content)?;
+    Ok(())
+}
+
+/// 写入一行执行日志，写入失败只打印错误
+pub fn write_job_log(log_file_name: Option<&str>, msg: &str) {
+    write_job_log_at(log_file_name, None, msg)
+}
+
+/// 日志行在调用线程格式化，交给日志写入线程追加到文件，不阻塞任务所在的线程
+pub fn write_job_log_at(log_file_name: Option<&str>, location: Option<(&str, u32)>, msg: &str) {
+    if let Some(log_file_name) = log_file_name {
+        send_job_log(JobLogMsg::Append(JobLogContent {
+            log_file_name: log_file_name.to_owned(),
+            content: format_log_line(location, msg).into_bytes(),
+        }));
+    }
+}
+
+/// 等待之前提交的执行日志都写入文件；
+/// 读取执行日志或让子进程直接写入日志文件前调用，保证内容完整且顺序一致；
+pub async fn flush_job_log() {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    send_job_log(JobLogMsg::Flush(Box::new(move || {
+        tx.send(()).ok();
+    })));
+    rx.await.ok();
+}
+
+/// 同步等待之前提交的执行日志都写入文件，不要在异步线程中调用
+fn flush_job_log_blocking() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    send_job_log(JobLogMsg::Flush(Box::new(move || {
+        tx.send(()).ok();
+    })));
+    rx.recv().ok();
+}
+
+struct JobLogContent {
+    log_file_name: String,
+    content: Vec<u8>,
+}
+
+impl JobLogContent {
+    fn append(&self) {
+        if let Err(e) = append_log_bytes(&self.log_file_name, &self.content) {
+            log::error!("write job log error:{},file:{}", e, &self.log_file_name);
+        }
+    }
+}
+
+enum JobLogMsg {
+    Append(JobLogContent),
+    /// 之前的日志都写入后回调
+    Flush(Box<dyn FnOnce() + Send>),
+}
+
+lazy_static::lazy_static! {
+    static ref JOB_LOG_SENDER: Sender<JobLogMsg> = start_job_log_writer();
+}
+
+fn send_job_log(msg: JobLogMsg) {
+    //日志写入线程没有启动时直接写入
+    if let Err(std::sync::mpsc::SendError(msg)) = JOB_LOG_SENDER.send(msg) {
+        match msg {
+            JobLogMsg::Append(content) => content.append(),
+            JobLogMsg::Flush(notify) => notify(),
+        }
+    }
+}
+
+/// 启动日志写入线程，按接收顺序写入；连续写入同一个文件的内容合并后一次写入
+fn start_job_log_writer() -> Sender<JobLogMsg> {
+    let (tx, rx) = std::sync::mpsc::channel::<JobLogMsg>();
+    let res = std::thread::Builder::new()
+        .name("xxljob-log-writer".to_owned())
+        .spawn(move || {
+            let mut pending = None;
+            loop {
+                let msg = match pending.take() {
+                    Some(v) => v,
+                    None => match rx.recv() {
+                        Ok(v) => v,
+                        Err(_) => return,
+                    },
+                };
+                match msg {
+                    JobLogMsg::Flush(notify) => notify(),
+                    JobLogMsg::Append(mut current) => {
+                        while let Ok(next) = rx.try_recv() {
+                            match next {
+                                JobLogMsg::Append(next)
+                                    if next.log_file_name == current.log_file_name =>
+                                {
+                                    current.content.extend_from_slice(&next.content);
+                                }
+                                other => {
+                                    pending = Some(other);
+                                    break;
+                                }
+                            }
+                        }
+                        current.append();
+                    }
+                }
+            }
+        });
+    if let Err(e) = res {
+        log::error!("job log writer start error:{}", e);
+    }
+    tx
+}
+
+/// 执行日志写入器，内容原样追加到本次执行的日志文件；
+/// 同时实现`std::io::Write`与`AsyncWrite`，可用于接入子进程输出或第三方日志；
+/// 与`JobContext::log`一样交给日志写入线程写入，写入顺序与调用顺序一致；`flush`等待之前的内容都写入文件；
+/// 没有设置log_path时写入的内容会被丢弃；
+#[derive(Debug)]
+pub struct JobLogWriter {
+    log_file_name: Option<String>,
+    flush_rx: Option<tokio::sync::oneshot::Receiver<()>>,
+}
+
+impl JobLogWriter {
+    pub fn new(log_file_name: Option<&str>) -> std::io::Result<Self> {
+        Ok(Self {
+            log_file_name: log_file_name.map(|v| v.to_owned()),
+            flush_rx: None,
+        })
+    }
+
+    /// 转换为子进程的标准输出/错误输出，子进程直接写入日志文件；
+    /// 需要先`flush`等待之前的日志写入，避免子进程的输出排在之前的日志前面；
+    pub fn to_stdio(&self) -> std::io::Result<Stdio> {
+        match &self.log_file_name {
+            Some(v) => Ok(Stdio::from(open_log_file(v)?)),
+            None => Ok(Stdio::null()),
+        }
+    }
+}
+
+impl Write for JobLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(log_file_name) = &self.log_file_name {
+            send_job_log(JobLogMsg::Append(JobLogContent {
+                log_file_name: log_file_name.clone(),
+                content: buf.to_vec(),
+            }));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.log_file_name.is_some() {
+            flush_job_log_blocking();
+        }
+        Ok(())
+    }
+}
+
+impl AsyncWrite for JobLogWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Write::write(self.get_mut(), buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if this.log_file_name.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        let flush_rx = this.flush_rx.get_or_insert_with(|| {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            send_job_log(JobLogMsg::Flush(Box::new(move || {
+                tx.send(()).ok();
+            })));
+            rx
+        });
+        match Pin::new(flush_rx).poll(cx) {
+            Poll::Ready(_) => {
+                this.flush_rx = None;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+/// 从from_line_num行(从1开始)读取执行日志
+pub fn read_log(log_file_name: &str, from_line_num: i32) -> LogResult {
+    let path = Path::new(log_file_name);
+    if !path.exists() {
+        return LogResult {
+            from_line_num,
+            to_line_num: 0,
+            log_content: "readLog fail, logFile not exists".to_owned(),
+            is_end: true,
+        };
+    }
+    let file = match std::fs::File::open(path) {
+        Ok(v) => v,
+        Err(e) => {
+            return LogResult {
+                from_line_num,
+                to_line_num: 0,
+                log_content: format!("readLog fail, {}", e),
+                is_end: false,
+            }
+        }
+    };
+    let mut log_content = String::new();
+    let mut to_line_num = 0;
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("read job log error:{},file:{}", e, log_file_name);
+                break;
+            }
+        };
+        to_line_num += 1;
+        if to_line_num >= from_line_num {
+            log_content.push_str(&line);
+            log_content.push('\n');
+        }
+    }
+    LogResult {
+        from_line_num,
+        to_line_num,
+        log_content,
+        is_end: false,
+    }
+}
+
+/// 清理过期的执行日志目录，只处理{log_path}下以日期命名的目录；
+/// 保留天数小于`MIN_LOG_RETENTION_DAYS`时不清理；返回删除的目录数量；
+pub fn clean_expired_logs(log_path: &str, log_retention_days: u32) -> usize {
+    if log_retention_days < MIN_LOG_RETENTION_DAYS {
+        return 0;
+    }
+    let dirs = match std::fs::read_dir(log_path) {
+        Ok(v) => v,
+        Err(_) => return 0,
+    };
+    let today = Local::now().date_naive();
+    let mut count = 0;
+    for entry in dirs.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let log_date = match path
+            .file_name()
+            .and_then(|v| v.to_str())
+            .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())
+        {
+            Some(v) => v,
+            None => continue,
+        };
+        if (today - log_date).num_days() < log_retention_days as i64 {
+            continue;
+        }
+        match std::fs::remove_dir_all(&path) {
+            Ok(_) => count += 1,
+            Err(e) => log::error!("remove expired job log error:{},path:{:?}", e, &path),
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    fn temp_log_file(name: &str) -> String {
+        let path = std::env::temp_dir()
+            .join("xxljob-sdk-rs-job-log-test")
+            .join(format!("{}-{}.log", name, std::process::id()));
+        std::fs::remove_file(&path).ok();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn write_job_log_in_order() {
+        let log_file_name = temp_log_file("lines");
+        for i in 0..100 {
+            write_job_log(Some(&log_file_name), &format!("line-{}", i));
+        }
+        flush_job_log().await;
+        let content = std::fs::read_to_string(&log_file_name).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 100);
+        for (i, line) in lines.iter().enumerate() {
+            assert!(line.ends_with(&format!(" line-{}", i)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn clean_expired_log_dirs() {
+        let log_path = std::env::temp_dir().join(format!(
+            "xxljob-sdk-rs-clean-log-test-{}",
+            std::process::id()
+        ));
+        std::fs::remove_dir_all(&log_path).ok();
+        let today = Local::now().date_naive();
+        let date_dir = |days: i64| {
+            log_path.join(
+                (today - chrono::Duration::days(days))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        };
+        for days in [0, 1, 6, 7, 30] {
+            std::fs::create_dir_all(date_dir(days)).unwrap();
+            std::fs::write(date_dir(days).join("1.log"), b"log").unwrap();
+        }
+        std::fs::create_dir_all(log_path.join("gluesource")).unwrap();
+        std::fs::create_dir_all(log_path.join("callbacklog")).unwrap();
+        let log_path_str = log_path.to_string_lossy();
+
+        //保留天数过小时不清理
+        assert_eq!(
+            clean_expired_logs(&log_path_str, MIN_LOG_RETENTION_DAYS - 1),
+            0
+        );
+        assert!(date_dir(30).exists());
+
+        assert_eq!(clean_expired_logs(&log_path_str, 7), 2);
+        for days in [0, 1, 6] {
+            assert!(date_dir(days).exists(), "{}", days);
+        }
+        assert!(!date_dir(7).exists());
+        assert!(!date_dir(30).exists());
+        assert!(log_path.join("gluesource").exists());
+        assert!(log_path.join("callbacklog").exists());
+        assert_eq!(clean_expired_logs(&log_path_str, 7), 0);
+        std::fs::remove_dir_all(&log_path).ok();
+    }
+
+    #[tokio::test]
+    async fn log_and_writer_in_order() {
+        let log_file_name = temp_log_file("mixed");
+        let mut writer = JobLogWriter::new(Some(&log_file_name)).unwrap();
+        for i in 0..20 {
+            write_job_log(Some(&log_file_name), &format!("line-{}", i));
+            AsyncWriteExt::write_all(&mut writer, format!("raw-{}\n", i).as_bytes())
+                .await
+                .unwrap();
+        }
+        flush_job_log().await;
+        let content = std::fs::read_to_string(&log_file_name).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 40);
+        for i in 0..20 {
+            assert!(lines[i * 2].ends_with(&format!(" line-{}", i)));
+            assert_eq!(lines[i * 2 + 1], format!("raw-{}", i));
+        }
+    }
+
+    #[tokio::test]
+    async fn async_log_writer() {
+        let log_file_name = temp_log_file("async");
+        let mut writer = JobLogWriter::new(Some(&log_file_name)).unwrap();
+        AsyncWriteExt::write_all(&mut writer, b"first\n")
+            .await
+            .unwrap();
+        AsyncWriteExt::write_all(&mut writer, b"second\n")
+            .await
+            .unwrap();
+        AsyncWriteExt::flush(&mut writer).await.unwrap();
+        let content = std::fs::read_to_string(&log_file_name).unwrap();
+        assert_eq!(content, "first\nsecond\n");
+
+        let mut writer = JobLogWriter::new(None).unwrap();
+        AsyncWriteExt::write_all(&mut writer, b"discard")
+            .await
+            .unwrap();
+        AsyncWriteExt::shutdown(&mut writer).await.unwrap();
+    }
+}
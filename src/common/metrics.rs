@@ -0,0 +1,450 @@
+use crate::common::client_state::JobHandlerStatus;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 任务执行耗时的分桶上限(秒)
+const JOB_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0, 600.0,
+    1800.0, 3600.0,
+];
+
+/// 注册心跳耗时的分桶上限(秒)
+const HEARTBEAT_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            counts: vec![0; buckets.len()],
+            sum: 0f64,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (i, bound) in self.buckets.iter().enumerate() {
+            if value <= *bound {
+                self.counts[i] += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn write(&self, out: &mut String, name: &str, label: &str) {
+        for (bound, count) in self.buckets.iter().zip(self.counts.iter()) {
+            writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, label, bound, count
+            )
+            .ok();
+        }
+        writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, label, self.count
+        )
+        .ok();
+        writeln!(out, "{}_sum{{{}}} {}", name, label, self.sum).ok();
+        writeln!(out, "{}_count{{{}}} {}", name, label, self.count).ok();
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HandlerMetrics {
+    runs: u64,
+    successes: u64,
+    failures: u64,
+    discards: u64,
+    duration: Histogram,
+}
+
+impl Default for HandlerMetrics {
+    fn default() -> Self {
+        Self {
+            runs: 0,
+            successes: 0,
+            failures: 0,
+            discards: 0,
+            duration: Histogram::new(JOB_DURATION_BUCKETS),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AdminAddrMetrics {
+    heartbeat_errors: u64,
+    heartbeat_duration: Histogram,
+}
+
+impl Default for AdminAddrMetrics {
+    fn default() -> Self {
+        Self {
+            heartbeat_errors: 0,
+            heartbeat_duration: Histogram::new(HEARTBEAT_DURATION_BUCKETS),
+        }
+    }
+}
+
+/// 执行器指标，开启`/metrics`后按Prometheus文本格式输出
+#[derive(Debug, Default)]
+pub struct ExecutorMetrics {
+    handlers: Mutex<HashMap<Arc<String>, HandlerMetrics>>,
+    admin_addrs: Mutex<HashMap<String, AdminAddrMetrics>>,
+    callback_failures: AtomicU64,
+    callback_retries: AtomicU64,
+}
+
+impl ExecutorMetrics {
+    fn update_handler<F: FnOnce(&mut HandlerMetrics)>(&self, name: &Arc<String>, f: F) {
+        if let Ok(mut handlers) = self.handlers.lock() {
+            f(handlers.entry(name.clone()).or_default());
+        }
+    }
+
+    pub fn record_job_start(&self, name: &Arc<String>) {
+        self.update_handler(name, |v| v.runs += 1);
+    }
+
+    pub fn record_job_finish(&self, name: &Arc<String>, success: bool, duration: Duration) {
+        self.update_handler(name, |v| {
+            if success {
+                v.successes += 1;
+            } else {
+                v.failures += 1;
+            }
+            v.duration.observe(duration.as_secs_f64());
+        });
+    }
+
+    /// 记录没有执行就被丢弃的任务
+    pub fn record_job_discard(&self, name: &Arc<String>, count: usize) {
+        if count > 0 {
+            self.update_handler(name, |v| v.discards += count as u64);
+        }
+    }
+
+    /// 移除任务处理器的指标，任务处理器注销或GLUE脚本更新时调用
+    pub fn remove_handler(&self, name: &Arc<String>) {
+        if let Ok(mut handlers) = self.handlers.lock() {
+            handlers.remove(name);
+        }
+    }
+
+    pub fn record_callback_failure(&self) {
+        self.callback_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_callback_retry(&self) {
+        self.callback_retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_heartbeat(&self, addr: &str, success: bool, duration: Duration) {
+        if let Ok(mut admin_addrs) = self.admin_addrs.lock() {
+            let v = admin_addrs.entry(addr.to_owned()).or_default();
+            if !success {
+                v.heartbeat_errors += 1;
+            }
+            v.heartbeat_duration.observe(duration.as_secs_f64());
+        }
+    }
+
+    /// 按Prometheus文本格式输出；handlers为查询时各任务处理器的运行与排队数量
+    pub fn render(&self, handlers: &[JobHandlerStatus]) -> String {
+        let mut out = String::new();
+        let mut handler_metrics: Vec<(Arc<String>, HandlerMetrics)> = self
+            .handlers
+            .lock()
+            .map(|v| v.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        handler_metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        write_handler_counter(
+            &mut out,
+            &handler_metrics,
+            "xxl_job_handler_runs_total",
+            "Job executions started",
+            |v| v.runs,
+        );
+        write_handler_counter(
+            &mut out,
+            &handler_metrics,
+            "xxl_job_handler_success_total",
+            "Job executions finished successfully",
+            |v| v.successes,
+        );
+        write_handler_counter(
+            &mut out,
+            &handler_metrics,
+            "xxl_job_handler_failure_total",
+            "Job executions failed, killed or timed out",
+            |v| v.failures,
+        );
+        write_handler_counter(
+            &mut out,
+            &handler_metrics,
+            "xxl_job_handler_discard_total",
+            "Jobs discarded before execution by block strategy, queue overflow or kill",
+            |v| v.discards,
+        );
+        let name = "xxl_job_handler_duration_seconds";
+        write_header(&mut out, name, "Job execution duration", "histogram");
+        for (handler, metrics) in handler_metrics.iter() {
+            let label = format!("handler=\"{}\"", escape_label(handler));
+            metrics.duration.write(&mut out, name, &label);
+        }
+
+        let mut handlers: Vec<&JobHandlerStatus> = handlers.iter().collect();
+        handlers.sort_by(|a, b| a.name.cmp(&b.name));
+        let name = "xxl_job_handler_queue_depth";
+        write_header(&mut out, name, "Jobs waiting in the serial queue", "gauge");
+        for v in handlers.iter() {
+            writeln!(
+                out,
+                "{}{{handler=\"{}\"}} {}",
+                name,
+                escape_label(&v.name),
+                v.queued_jobs
+            )
+            .ok();
+        }
+        let name = "xxl_job_handler_running_jobs";
+        write_header(&mut out, name, "Jobs currently running", "gauge");
+        for v in handlers.iter() {
+            writeln!(
+                out,
+                "{}{{handler=\"{}\"}} {}",
+                name,
+                escape_label(&v.name),
+                v.running_jobs
+            )
+            .ok();
+        }
+
+        let name = "xxl_job_callback_failures_total";
+        write_header(
+            &mut out,
+            name,
+            "Failed callback requests to the admin",
+            "counter",
+        );
+        writeln!(
+            out,
+            "{} {}",
+            name,
+            self.callback_failures.load(Ordering::Relaxed)
+        )
+        .ok();
+        let name = "xxl_job_callback_retries_total";
+        write_header(&mut out, name, "Callback retry attempts", "counter");
+        writeln!(
+            out,
+            "{} {}",
+            name,
+            self.callback_retries.load(Ordering::Relaxed)
+        )
+        .ok();
+
+        let mut admin_metrics: Vec<(String, AdminAddrMetrics)> = self
+            .admin_addrs
+            .lock()
+            .map(|v| v.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        admin_metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        let name = "xxl_job_registry_heartbeat_errors_total";
+        write_header(
+            &mut out,
+            name,
+            "Failed registry heartbeat requests",
+            "counter",
+        );
+        for (addr, metrics) in admin_metrics.iter() {
+            writeln!(
+                out,
+                "{}{{admin_addr=\"{}\"}} {}",
+                name,
+                escape_label(addr),
+                metrics.heartbeat_errors
+            )
+            .ok();
+        }
+        let name = "xxl_job_registry_heartbeat_duration_seconds";
+        write_header(
+            &mut out,
+            name,
+            "Registry heartbeat request latency",
+            "histogram",
+        );
+        for (addr, metrics) in admin_metrics.iter() {
+            let label = format!("admin_addr=\"{}\"", escape_label(addr));
+            metrics.heartbeat_duration.write(&mut out, name, &label);
+        }
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, metric_type: &str) {
+    writeln!(out, "# HELP {} {}", name, help).ok();
+    writeln!(out, "# TYPE {} {}", name, metric_type).ok();
+}
+
+fn write_handler_counter(
+    out: &mut String,
+    handler_metrics: &[(Arc<String>, HandlerMetrics)],
+    name: &str,
+    help: &str,
+    value: fn(&HandlerMetrics) -> u64,
+) {
+    write_header(out, name, help, "counter");
+    for (handler, metrics) in handler_metrics.iter() {
+        writeln!(
+            out,
+            "{}{{handler=\"{}\"}} {}",
+            name,
+            escape_label(handler),
+            value(metrics)
+        )
+        .ok();
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(out: &str) -> Vec<&str> {
+        out.lines().collect()
+    }
+
+    #[test]
+    fn render_handler_metrics() {
+        let metrics = ExecutorMetrics::default();
+        let name = Arc::new("demo\"job\\x\n".to_owned());
+        metrics.record_job_start(&name);
+        metrics.record_job_start(&name);
+        metrics.record_job_finish(&name, true, Duration::from_millis(200));
+        metrics.record_job_finish(&name, false, Duration::from_secs(3));
+        metrics.record_job_discard(&name, 2);
+        metrics.record_job_discard(&name, 0);
+        let handlers = vec![JobHandlerStatus {
+            name: name.clone(),
+            running_jobs: 1,
+            queued_jobs: 3,
+        }];
+        let out = metrics.render(&handlers);
+        let lines = lines(&out);
+        let label = r#"handler="demo\"job\\x\n""#;
+        for line in [
+            "# HELP xxl_job_handler_runs_total Job executions started".to_owned(),
+            "# TYPE xxl_job_handler_runs_total counter".to_owned(),
+            format!("xxl_job_handler_runs_total{{{}}} 2", label),
+            format!("xxl_job_handler_success_total{{{}}} 1", label),
+            format!("xxl_job_handler_failure_total{{{}}} 1", label),
+            format!("xxl_job_handler_discard_total{{{}}} 2", label),
+            "# TYPE xxl_job_handler_duration_seconds histogram".to_owned(),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"0.1\"}} 0",
+                label
+            ),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"0.25\"}} 1",
+                label
+            ),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"2.5\"}} 1",
+                label
+            ),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"5\"}} 2",
+                label
+            ),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"3600\"}} 2",
+                label
+            ),
+            format!(
+                "xxl_job_handler_duration_seconds_bucket{{{},le=\"+Inf\"}} 2",
+                label
+            ),
+            format!("xxl_job_handler_duration_seconds_sum{{{}}} 3.2", label),
+            format!("xxl_job_handler_duration_seconds_count{{{}}} 2", label),
+            format!("xxl_job_handler_queue_depth{{{}}} 3", label),
+            format!("xxl_job_handler_running_jobs{{{}}} 1", label),
+        ] {
+            assert!(lines.contains(&line.as_str()), "{}\n{}", line, out);
+        }
+        let bucket_count = lines
+            .iter()
+            .filter(|v| v.starts_with("xxl_job_handler_duration_seconds_bucket"))
+            .count();
+        assert_eq!(bucket_count, JOB_DURATION_BUCKETS.len() + 1);
+    }
+
+    #[test]
+    fn remove_handler_metrics() {
+        let metrics = ExecutorMetrics::default();
+        let removed = Arc::new("removed".to_owned());
+        let kept = Arc::new("kept".to_owned());
+        metrics.record_job_start(&removed);
+        metrics.record_job_start(&kept);
+        metrics.remove_handler(&removed);
+        let out = metrics.render(&[]);
+        assert!(!out.contains(r#"handler="removed""#), "{}", out);
+        assert!(lines(&out).contains(&r#"xxl_job_handler_runs_total{handler="kept"} 1"#));
+    }
+
+    #[test]
+    fn render_callback_and_heartbeat_metrics() {
+        let metrics = ExecutorMetrics::default();
+        metrics.record_callback_failure();
+        metrics.record_callback_retry();
+        metrics.record_callback_retry();
+        metrics.record_heartbeat("http://admin-b", true, Duration::from_millis(20));
+        metrics.record_heartbeat("http://admin-b", false, Duration::from_millis(300));
+        metrics.record_heartbeat("http://admin-a", true, Duration::from_millis(1));
+        let out = metrics.render(&[]);
+        let lines = lines(&out);
+        for line in [
+            "xxl_job_callback_failures_total 1",
+            "xxl_job_callback_retries_total 2",
+            r#"xxl_job_registry_heartbeat_errors_total{admin_addr="http://admin-a"} 0"#,
+            r#"xxl_job_registry_heartbeat_errors_total{admin_addr="http://admin-b"} 1"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_bucket{admin_addr="http://admin-b",le="0.01"} 0"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_bucket{admin_addr="http://admin-b",le="0.025"} 1"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_bucket{admin_addr="http://admin-b",le="0.25"} 1"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_bucket{admin_addr="http://admin-b",le="0.5"} 2"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_bucket{admin_addr="http://admin-b",le="+Inf"} 2"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_sum{admin_addr="http://admin-b"} 0.32"#,
+            r#"xxl_job_registry_heartbeat_duration_seconds_count{admin_addr="http://admin-b"} 2"#,
+        ] {
+            assert!(lines.contains(&line), "{}\n{}", line, out);
+        }
+        //地址按名称排序输出
+        let a = out.find("admin_addr=\"http://admin-a\"").unwrap();
+        let b = out.find("admin_addr=\"http://admin-b\"").unwrap();
+        assert!(a < b);
+        //没有任务处理器时只输出指标说明
+        assert!(!out.contains("handler=\""));
+        assert!(lines.contains(&"# TYPE xxl_job_handler_queue_depth gauge"));
+    }
+}
@@ -1,10 +1,15 @@
 pub mod actor_utils;
 pub mod client_config;
+pub mod client_state;
 pub mod constant;
 pub mod http_utils;
 pub mod ip_utils;
+pub mod job_log;
+pub mod metrics;
 pub mod model;
+pub mod sharding;
 pub mod share_data;
+pub mod worker_pool;
 
 pub fn now_millis() -> u64 {
     use std::time::SystemTime;
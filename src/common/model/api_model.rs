@@ -24,3 +24,20 @@ pub struct JobRunParam {
 pub struct JobIdleBeatParam {
     pub job_id: u64,
 }
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogParam {
+    pub log_date_tim: i64,
+    pub log_id: u64,
+    pub from_line_num: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogResult {
+    pub from_line_num: i32,
+    pub to_line_num: i32,
+    pub log_content: String,
+    pub is_end: bool,
+}
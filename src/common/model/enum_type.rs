@@ -99,3 +99,27 @@ impl ExecutorBlockStrategy {
         }
     }
 }
+
+/// 串行执行时排队任务超过上限的处理方式
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum QueueOverflowStrategy {
+    /// 移除最早排队的任务
+    #[default]
+    DropOldest,
+    /// 拒绝新的任务
+    RejectNewest,
+    /// 不限制排队数量
+    Unbounded,
+}
+
+/// 服务端地址选择策略
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum AdminAddrSelectStrategy {
+    /// 轮询
+    RoundRobin,
+    /// 一直使用同一个地址，失败后切换到下一个地址
+    #[default]
+    StickyUntilFailure,
+    /// 随机
+    Random,
+}
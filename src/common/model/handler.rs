@@ -1,10 +1,180 @@
+use crate::common::job_log::{make_log_file_name, write_job_log, write_job_log_at, JobLogWriter};
 use crate::common::model::api_model::JobRunParam;
-use crate::common::model::enum_type::{ExecutorBlockStrategy, GlueType};
+use crate::common::model::enum_type::{ExecutorBlockStrategy, GlueType, QueueOverflowStrategy};
+use crate::common::model::interceptor::JobInterceptor;
+use crate::common::model::job_param::parse_job_param;
 use crate::common::model::{FAIL_CODE, SUCCESS_CODE};
+use crate::common::now_millis_i64;
+use crate::common::sharding::Sharding;
 use crate::common::share_data::ShareData;
 use crate::executor::admin_server;
 use async_trait::async_trait;
-use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// 任务取消标记；
+/// 终止任务时记录取消原因并唤醒等待取消的任务；
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JobCancelToken {
+    token: CancellationToken,
+    reason: Arc<Mutex<Option<(i32, String)>>>,
+}
+
+impl JobCancelToken {
+    /// 取消任务，只保留第一次取消的原因
+    pub fn cancel(&self, reason: String) {
+        self.cancel_with_code(FAIL_CODE, reason)
+    }
+
+    /// 取消任务并指定反馈的结果码
+    pub fn cancel_with_code(&self, handle_code: i32, reason: String) {
+        if let Ok(mut r) = self.reason.lock() {
+            if r.is_none() {
+                *r = Some((handle_code, reason));
+            }
+        }
+        self.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// 按取消原因构造任务错误
+    pub fn error(&self) -> anyhow::Error {
+        let (handle_code, reason) = self
+            .reason
+            .lock()
+            .ok()
+            .and_then(|r| r.clone())
+            .unwrap_or_else(|| (FAIL_CODE, "job cancelled".to_owned()));
+        JobHandleError::new(handle_code, reason).into()
+    }
+}
+
+/// 带处理结果码的任务错误；
+/// 任务处理器返回该错误时，会使用其中的handle_code回调服务端；
+#[derive(Debug, Clone)]
+pub struct JobHandleError {
+    pub handle_code: i32,
+    pub handle_msg: String,
+}
+
+impl JobHandleError {
+    pub fn new(handle_code: i32, handle_msg: String) -> Self {
+        Self {
+            handle_code,
+            handle_msg,
+        }
+    }
+
+    /// 获取错误对应的处理结果码，默认为失败
+    pub fn get_handle_code(err: &anyhow::Error) -> i32 {
+        err.downcast_ref::<JobHandleError>()
+            .map(|e| e.handle_code)
+            .unwrap_or(FAIL_CODE)
+    }
+}
+
+/// 任务处理结果，按handle_code与handle_msg反馈到服务端；
+/// handle_msg会显示在服务端的调度日志中，例如"processed 10,234 rows"；
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobOutcome {
+    /// 执行成功，可附带说明
+    Success(Option<String>),
+    /// 执行失败，code一般使用`FAIL_CODE`，非200的结果码服务端都按失败处理
+    Failure { code: i32, msg: String },
+    /// 没有需要处理的内容，跳过执行；按成功反馈，附带跳过原因
+    Skipped(Option<String>),
+    /// 部分成功；按成功反馈，说明中需要描述失败的部分，需要服务端告警或重试时使用`Failure`
+    Partial(String),
+}
+
+impl Default for JobOutcome {
+    fn default() -> Self {
+        Self::Success(None)
+    }
+}
+
+impl From<()> for JobOutcome {
+    fn from(_: ()) -> Self {
+        Self::Success(None)
+    }
+}
+
+impl JobOutcome {
+    pub fn success() -> Self {
+        Self::Success(None)
+    }
+
+    pub fn success_with_msg<S: Into<String>>(msg: S) -> Self {
+        Self::Success(Some(msg.into()))
+    }
+
+    pub fn failure<S: Into<String>>(msg: S) -> Self {
+        Self::Failure {
+            code: FAIL_CODE,
+            msg: msg.into(),
+        }
+    }
+
+    pub fn failure_with_code<S: Into<String>>(code: i32, msg: S) -> Self {
+        Self::Failure {
+            code,
+            msg: msg.into(),
+        }
+    }
+
+    pub fn skipped<S: Into<String>>(reason: S) -> Self {
+        Self::Skipped(Some(reason.into()))
+    }
+
+    pub fn partial<S: Into<String>>(msg: S) -> Self {
+        Self::Partial(msg.into())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.handle_code() == SUCCESS_CODE
+    }
+
+    /// 回调服务端的handle_code
+    pub fn handle_code(&self) -> i32 {
+        match self {
+            JobOutcome::Failure { code, .. } => *code,
+            _ => SUCCESS_CODE,
+        }
+    }
+
+    /// 回调服务端的handle_msg
+    pub fn handle_msg(&self) -> Option<String> {
+        match self {
+            JobOutcome::Success(msg) => msg.clone(),
+            JobOutcome::Failure { msg, .. } => Some(msg.clone()),
+            JobOutcome::Skipped(reason) => Some(match reason {
+                Some(reason) => format!("job skipped: {}", reason),
+                None => "job skipped".to_owned(),
+            }),
+            JobOutcome::Partial(msg) => Some(format!("job partial success: {}", msg)),
+        }
+    }
+}
+
+impl Display for JobHandleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", &self.handle_msg)
+    }
+}
+
+impl std::error::Error for JobHandleError {}
 
 #[derive(Debug, Clone)]
 pub struct JobContext {
@@ -14,19 +184,42 @@ pub struct JobContext {
     pub log_id: u64,
     pub shard_index: u64,
     pub shard_total: u64,
-    pub handle_code: i32,
-    pub handle_msg: Option<String>,
     pub block_strategy: ExecutorBlockStrategy,
     pub glue_type: GlueType,
+    /// 执行超时时间，服务端没有设置时为None
+    pub executor_timeout: Option<Duration>,
+    pub(crate) deadline: Option<Instant>,
     pub(crate) share_data: Arc<ShareData>,
+    pub(crate) cancel_token: JobCancelToken,
+    #[cfg(feature = "tracing")]
+    pub(crate) span: tracing::Span,
 }
 
 impl JobContext {
     pub fn new(run_param: JobRunParam, share_data: Arc<ShareData>) -> Self {
+        let log_date_time = run_param
+            .log_date_time
+            .map(|v| v as i64)
+            .unwrap_or_else(now_millis_i64);
+        //任务处理器名称与阻塞处理策略在执行器确定后再记录
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "xxl_job",
+            job_id = run_param.job_id,
+            log_id = run_param.log_id,
+            handler = tracing::field::Empty,
+            shard_index = run_param.broadcast_index.unwrap_or(0),
+            shard_total = run_param.broadcast_total.unwrap_or(1),
+            block_strategy = tracing::field::Empty,
+        );
         Self {
             job_id: run_param.job_id,
             job_param: run_param.executor_params,
-            job_log_file_name: None,
+            job_log_file_name: make_log_file_name(
+                &share_data.client_config.log_path,
+                log_date_time,
+                run_param.log_id,
+            ),
             log_id: run_param.log_id,
             shard_index: run_param.broadcast_index.unwrap_or(0),
             shard_total: run_param.broadcast_total.unwrap_or(1),
@@ -35,31 +228,79 @@ impl JobContext {
             block_strategy: ExecutorBlockStrategy::from_str(
                 &run_param.executor_block_strategy.unwrap_or_default(),
             ),
-            handle_code: SUCCESS_CODE,
-            handle_msg: None,
+            executor_timeout: run_param
+                .executor_timeout
+                .filter(|v| *v > 0)
+                .map(|v| Duration::from_secs(v as u64)),
+            deadline: None,
             share_data,
+            cancel_token: JobCancelToken::default(),
+            #[cfg(feature = "tracing")]
+            span,
         }
     }
 
-    pub fn callback_success(&self) {
-        admin_server::callback_success(&self.share_data.server_access_actor, self.log_id);
+    /// 本次执行的tracing span；任务处理器中另外启动的异步任务或线程可以用它关联到同一个trace
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
     }
 
-    pub fn callback_failed(&self) {
-        let handle_code = if self.handle_code != SUCCESS_CODE {
-            self.handle_code
-        } else {
-            FAIL_CODE
-        };
-        admin_server::callback(
-            &self.share_data.server_access_actor,
-            self.log_id,
-            handle_code,
-            self.handle_msg.clone(),
-        );
+    /// 按类型解析任务参数；
+    /// 支持json、`key=value`查询字符串(`&`或空白分隔)与空白分隔的参数列表，例如：
+    /// `{"id":1}`、`id=1&name=a`可解析为结构体，`1 a`可解析为`(u64, String)`或`Vec<String>`；
+    /// 参数为空时只能解析为`Option<T>`、`()`等可以接收null的类型；
+    pub fn param_as<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        parse_job_param(self.job_param.as_deref())
+    }
+
+    /// 当前执行器的分片，按shard_index与shard_total判断数据是否由当前执行器处理；
+    /// 非广播任务时只有一个分片
+    pub fn sharding(&self) -> Sharding {
+        Sharding::new(self.shard_index, self.shard_total)
+    }
+
+    /// 开始执行时计算超时截止时间
+    pub(crate) fn start_timeout(&mut self) {
+        self.deadline = self.executor_timeout.map(|v| Instant::now() + v);
+    }
+
+    /// 距离执行超时的剩余时间，没有设置超时时返回None
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.deadline
+            .map(|v| v.saturating_duration_since(Instant::now()))
+    }
+
+    /// 写入一行本次执行的日志，可在服务端「执行日志」中查看；
+    /// 没有设置log_path时不记录；
+    pub fn log<S: AsRef<str>>(&self, msg: S) {
+        write_job_log(self.job_log_file_name.as_deref(), msg.as_ref());
+    }
+
+    /// 供`xxl_log!`使用，日志带上调用位置
+    #[doc(hidden)]
+    pub fn log_at(&self, source: &str, line: u32, msg: &str) {
+        write_job_log_at(self.job_log_file_name.as_deref(), Some((source, line)), msg);
+    }
+
+    /// 获取本次执行的日志写入器，写入内容原样追加到执行日志中
+    pub fn log_writer(&self) -> std::io::Result<JobLogWriter> {
+        JobLogWriter::new(self.job_log_file_name.as_deref())
+    }
+
+    /// 任务是否已被终止；
+    /// 同步任务处理器需要在处理过程中主动检查，被终止后尽快退出；
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.is_cancelled()
     }
 
-    pub fn callback_failed_with_info(&self, error_msg: String, handle_code: i32) {
+    /// 等待任务被终止
+    pub async fn cancelled(&self) {
+        self.cancel_token.cancelled().await
+    }
+
+    /// 任务未执行时直接反馈失败
+    pub(crate) fn callback_failed_with_info(&self, error_msg: String, handle_code: i32) {
         admin_server::callback(
             &self.share_data.server_access_actor,
             self.log_id,
@@ -74,14 +315,45 @@ impl JobContext {
 /// 只使用一个异步线程运行所有任务，不要在内容写同步堵塞线程逻辑；
 #[async_trait]
 pub trait AsyncJobHandler: Send + Sync {
-    async fn process(&self, context: JobContext) -> anyhow::Result<JobContext>;
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome>;
 }
 
 /// 同步任务处理器；
-/// 每个任务起一个线程运行，CPU密集型任务推荐使用；
-/// 任务数量多后线程数量不可控，后续考虑支持放到线程池运行；
+/// 在同步任务线程池中运行，CPU密集型任务推荐使用；
+/// 线程池的最大线程数与排队上限可通过`XxlClientBuilder`设置，线程池满时任务直接失败；
+/// 线程无法被强制终止，任务被终止后需要通过`JobContext::is_cancelled`主动退出；
 pub trait SyncJobHandler: Send + Sync {
-    fn process(&self, context: JobContext) -> anyhow::Result<JobContext>;
+    fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome>;
+}
+
+/// 带类型参数的异步任务处理器；
+/// 执行前先按`JobContext::param_as`解析任务参数，解析失败时直接反馈失败，不再调用`process`；
+/// 通过`JobHandler::typed`注册；
+#[async_trait]
+pub trait TypedJobHandler<P>: Send + Sync
+where
+    P: DeserializeOwned + Send + 'static,
+{
+    async fn process(&self, context: JobContext, param: P) -> anyhow::Result<JobOutcome>;
+}
+
+struct TypedJobHandlerAdapter<P, H> {
+    handler: Arc<H>,
+    _param: PhantomData<fn() -> P>,
+}
+
+#[async_trait]
+impl<P, H> AsyncJobHandler for TypedJobHandlerAdapter<P, H>
+where
+    P: DeserializeOwned + Send + 'static,
+    H: TypedJobHandler<P> + 'static,
+{
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
+        let param = context
+            .param_as::<P>()
+            .map_err(|e| JobHandleError::new(FAIL_CODE, e.to_string()))?;
+        self.handler.process(context, param).await
+    }
 }
 
 /// 任务处理器；
@@ -92,12 +364,23 @@ pub enum JobHandler {
     /// 只使用一个异步线程运行所有任务，不要在内容写同步堵塞线程逻辑；
     Async(Arc<dyn AsyncJobHandler>),
     /// 同步任务处理器；
-    /// 每个任务起一个线程运行，CPU密集型任务推荐使用；
-    /// 任务数量多后线程数量不可控，后续考虑支持放到线程池运行；
+    /// 在同步任务线程池中运行，CPU密集型任务推荐使用；
     Sync(Arc<dyn SyncJobHandler>),
 }
 
 impl JobHandler {
+    /// 使用带类型参数的任务处理器
+    pub fn typed<P, H>(handler: Arc<H>) -> Self
+    where
+        P: DeserializeOwned + Send + 'static,
+        H: TypedJobHandler<P> + 'static,
+    {
+        Self::Async(Arc::new(TypedJobHandlerAdapter {
+            handler,
+            _param: PhantomData,
+        }))
+    }
+
     pub fn is_async(&self) -> bool {
         match self {
             JobHandler::Async(_) => true,
@@ -132,42 +415,135 @@ impl From<Arc<dyn SyncJobHandler>> for JobHandler {
     }
 }
 
+/// 通过`#[xxl_job]`声明的任务处理器，构建客户端时自动注册
+#[cfg(feature = "macros")]
+pub struct JobHandlerRegistration {
+    pub name: &'static str,
+    pub handler: fn() -> JobHandler,
+}
+
+#[cfg(feature = "macros")]
+inventory::collect!(JobHandlerRegistration);
+
+/// 任务处理器配置，注册任务处理器时设置
+#[derive(Clone, Debug)]
+pub struct JobHandlerConfig {
+    /// 串行执行时最多排队的任务数量
+    pub queue_capacity: usize,
+    /// 排队任务超过上限的处理方式
+    pub overflow_strategy: QueueOverflowStrategy,
+    /// 强制使用的阻塞处理策略，设置后忽略服务端下发的策略
+    pub block_strategy: Option<ExecutorBlockStrategy>,
+    /// 只作用于该任务处理器的拦截器，在全局拦截器之后调用
+    pub interceptors: Vec<Arc<dyn JobInterceptor>>,
+}
+
+impl Default for JobHandlerConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 10,
+            overflow_strategy: QueueOverflowStrategy::default(),
+            block_strategy: None,
+            interceptors: vec![],
+        }
+    }
+}
+
+impl JobHandlerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    pub fn set_overflow_strategy(mut self, overflow_strategy: QueueOverflowStrategy) -> Self {
+        self.overflow_strategy = overflow_strategy;
+        self
+    }
+
+    pub fn set_block_strategy(mut self, block_strategy: ExecutorBlockStrategy) -> Self {
+        self.block_strategy = Some(block_strategy);
+        self
+    }
+
+    pub fn add_interceptor(mut self, interceptor: Arc<dyn JobInterceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+}
+
+/// 运行中的任务
+#[derive(Clone, Debug)]
+pub(crate) struct RunningJob {
+    pub job_id: u64,
+    pub cancel_token: JobCancelToken,
+}
+
 #[derive(Clone)]
 pub struct JobHandlerValue {
     pub handler: JobHandler,
     pub name: Arc<String>,
-    pub is_running: bool,
-    pub last_run_id: u64,
     pub block_jobs: Vec<JobContext>,
+    pub config: JobHandlerConfig,
+    pub(crate) running_jobs: HashMap<u64, RunningJob>,
+    /// 已移除注册，等运行中的任务结束后再清理
+    pub(crate) draining: bool,
 }
 
 #[derive(Clone)]
 pub struct JobHandlerRunParam {
     pub handler: JobHandler,
     pub name: Arc<String>,
+    pub interceptors: Vec<Arc<dyn JobInterceptor>>,
 }
 
 impl JobHandlerValue {
     pub fn new(name: Arc<String>, handler: JobHandler) -> Self {
+        Self::new_with_config(name, handler, JobHandlerConfig::default())
+    }
+
+    pub fn new_with_config(
+        name: Arc<String>,
+        handler: JobHandler,
+        config: JobHandlerConfig,
+    ) -> Self {
         Self {
             handler,
             name,
-            is_running: false,
-            last_run_id: 0,
             block_jobs: Vec::with_capacity(2),
+            config,
+            running_jobs: HashMap::new(),
+            draining: false,
         }
     }
+
+    /// 是否有运行中的任务
+    pub fn is_running(&self) -> bool {
+        !self.running_jobs.is_empty()
+    }
+
+    /// 加入排队任务，返回因超过排队上限而未能排队的任务
     pub fn push_block_job(&mut self, job: JobContext) -> Option<JobContext> {
-        if !self.is_running {
+        if !self.is_running() {
             return None;
         }
-        if self.block_jobs.len() >= 10 {
-            let remove = self.block_jobs.remove(0);
+        if self.block_jobs.len() < self.config.queue_capacity {
             self.block_jobs.push(job);
-            Some(remove)
-        } else {
-            self.block_jobs.push(job);
-            None
+            return None;
+        }
+        match self.config.overflow_strategy {
+            QueueOverflowStrategy::DropOldest => {
+                self.block_jobs.push(job);
+                Some(self.block_jobs.remove(0))
+            }
+            QueueOverflowStrategy::RejectNewest => Some(job),
+            QueueOverflowStrategy::Unbounded => {
+                self.block_jobs.push(job);
+                None
+            }
         }
     }
 
@@ -179,10 +555,158 @@ impl JobHandlerValue {
         }
     }
 
+    /// 标记任务开始运行
+    pub(crate) fn start_job(&mut self, job: &JobContext) -> JobHandlerRunParam {
+        self.running_jobs.insert(
+            job.log_id,
+            RunningJob {
+                job_id: job.job_id,
+                cancel_token: job.cancel_token.clone(),
+            },
+        );
+        self.build_run_param()
+    }
+
+    /// 标记任务运行结束
+    pub(crate) fn finish_job(&mut self, log_id: u64) {
+        self.running_jobs.remove(&log_id);
+    }
+
+    /// 终止job_id对应的运行中任务，并移除排队中的任务；
+    /// 返回被终止的运行中任务数量与被移除的排队任务；
+    pub(crate) fn kill_job(&mut self, job_id: u64, reason: &str) -> (usize, Vec<JobContext>) {
+        self.cancel_jobs(|v| v == job_id, reason)
+    }
+
+    /// 终止所有运行中任务，并移除所有排队中的任务
+    pub(crate) fn kill_all_jobs(&mut self, reason: &str) -> (usize, Vec<JobContext>) {
+        self.cancel_jobs(|_| true, reason)
+    }
+
+    fn cancel_jobs<F>(&mut self, filter: F, reason: &str) -> (usize, Vec<JobContext>)
+    where
+        F: Fn(u64) -> bool,
+    {
+        let mut kill_count = 0;
+        for job in self.running_jobs.values() {
+            if filter(job.job_id) {
+                job.cancel_token
+                    .cancel(format!("{} [job running, killed]", reason));
+                kill_count += 1;
+            }
+        }
+        let (removed, block_jobs) = std::mem::take(&mut self.block_jobs)
+            .into_iter()
+            .partition(|job| filter(job.job_id));
+        self.block_jobs = block_jobs;
+        (kill_count, removed)
+    }
+
     pub fn build_run_param(&self) -> JobHandlerRunParam {
         JobHandlerRunParam {
             handler: self.handler.clone(),
             name: self.name.clone(),
+            interceptors: self.config.interceptors.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::client_config::ClientConfig;
+    use crate::common::client_state::ClientState;
+    use actix::AsyncContext;
+
+    struct NoopJobHandler;
+
+    impl SyncJobHandler for NoopJobHandler {
+        fn process(&self, _context: JobContext) -> anyhow::Result<JobOutcome> {
+            Ok(JobOutcome::success())
         }
     }
+
+    fn job_context(log_id: u64) -> JobContext {
+        //回调发送到没有运行的actor，直接丢弃
+        let share_data = Arc::new(ShareData {
+            executor_actor: actix::Context::new().address(),
+            server_access_actor: actix::Context::new().address(),
+            server_runner: actix::Context::new().address(),
+            client_config: Arc::new(ClientConfig::default()),
+            client_state: Arc::new(ClientState::default()),
+        });
+        JobContext::new(
+            JobRunParam {
+                job_id: 1,
+                log_id,
+                ..Default::default()
+            },
+            share_data,
+        )
+    }
+
+    fn handler_value(capacity: usize, overflow_strategy: QueueOverflowStrategy) -> JobHandlerValue {
+        let mut value = JobHandlerValue::new_with_config(
+            Arc::new("test".to_owned()),
+            JobHandler::Sync(Arc::new(NoopJobHandler)),
+            JobHandlerConfig::new()
+                .set_queue_capacity(capacity)
+                .set_overflow_strategy(overflow_strategy),
+        );
+        value.start_job(&job_context(1));
+        value
+    }
+
+    fn block_log_ids(value: &JobHandlerValue) -> Vec<u64> {
+        value.block_jobs.iter().map(|v| v.log_id).collect()
+    }
+
+    #[test]
+    fn push_block_job_not_running() {
+        let mut value = handler_value(1, QueueOverflowStrategy::RejectNewest);
+        value.finish_job(1);
+        assert!(value.push_block_job(job_context(2)).is_none());
+        assert!(value.block_jobs.is_empty());
+    }
+
+    #[test]
+    fn overflow_drop_oldest() {
+        let mut value = handler_value(2, QueueOverflowStrategy::DropOldest);
+        assert!(value.push_block_job(job_context(2)).is_none());
+        assert!(value.push_block_job(job_context(3)).is_none());
+        let dropped = value.push_block_job(job_context(4)).unwrap();
+        assert_eq!(dropped.log_id, 2);
+        assert_eq!(block_log_ids(&value), vec![3, 4]);
+    }
+
+    #[test]
+    fn overflow_reject_newest() {
+        let mut value = handler_value(2, QueueOverflowStrategy::RejectNewest);
+        assert!(value.push_block_job(job_context(2)).is_none());
+        assert!(value.push_block_job(job_context(3)).is_none());
+        let rejected = value.push_block_job(job_context(4)).unwrap();
+        assert_eq!(rejected.log_id, 4);
+        assert_eq!(block_log_ids(&value), vec![2, 3]);
+        assert_eq!(value.pop_block_job().unwrap().log_id, 2);
+        assert!(value.push_block_job(job_context(5)).is_none());
+        assert_eq!(block_log_ids(&value), vec![3, 5]);
+    }
+
+    #[test]
+    fn overflow_unbounded() {
+        let mut value = handler_value(1, QueueOverflowStrategy::Unbounded);
+        for log_id in 2..12 {
+            assert!(value.push_block_job(job_context(log_id)).is_none());
+        }
+        assert_eq!(block_log_ids(&value), (2..12).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut value = handler_value(0, QueueOverflowStrategy::DropOldest);
+        //没有排队任务可移除时，丢弃的就是新任务
+        let dropped = value.push_block_job(job_context(2)).unwrap();
+        assert_eq!(dropped.log_id, 2);
+        assert!(value.block_jobs.is_empty());
+    }
 }
@@ -0,0 +1,42 @@
+use crate::common::model::handler::{JobContext, JobOutcome};
+use async_trait::async_trait;
+use std::fmt::{Debug, Formatter};
+
+/// 任务拦截器，在任务处理器执行前后调用，同时作用于异步与同步任务处理器；
+/// 可以在`XxlClientBuilder::add_interceptor`注册为全局拦截器，
+/// 也可以通过`JobHandlerConfig::add_interceptor`只作用于单个任务处理器；
+/// 多个拦截器按全局、任务处理器的注册顺序调用`before`，按相反顺序调用`after`与`on_error`；
+/// 只有`before`返回`Ok(None)`的拦截器才会调用`after`或`on_error`；
+/// 任务终止与执行超时作用于整个拦截器链，之后拦截器链最多再运行3秒用于处理终止错误；
+#[async_trait]
+pub trait JobInterceptor: Send + Sync {
+    /// 执行前调用，可以修改context；
+    /// 返回`Ok(Some(outcome))`时不再执行任务处理器，直接使用该结果；返回`Err`时任务按失败反馈；
+    async fn before(&self, _context: &mut JobContext) -> anyhow::Result<Option<JobOutcome>> {
+        Ok(None)
+    }
+
+    /// 执行成功后调用，可以修改结果
+    async fn after(
+        &self,
+        _context: &JobContext,
+        outcome: JobOutcome,
+    ) -> anyhow::Result<JobOutcome> {
+        Ok(outcome)
+    }
+
+    /// 执行失败后调用，包含任务被终止与执行超时；可以把错误转换为结果，或返回新的错误
+    async fn on_error(
+        &self,
+        _context: &JobContext,
+        error: anyhow::Error,
+    ) -> anyhow::Result<JobOutcome> {
+        Err(error)
+    }
+}
+
+impl Debug for dyn JobInterceptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JobInterceptor")
+    }
+}
@@ -0,0 +1,135 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// 解析任务参数；
+/// 依次尝试json、`key=value`查询字符串(`&`或空白分隔)、空白分隔的参数列表，最后把整个参数当作字符串；
+/// 参数为空时按null解析，可使用`Option<T>`接收；
+/// 解析失败时返回参数格式对应的错误原因：以`{`或`[`开头时为json错误，包含`=`时为查询字符串错误；
+pub fn parse_job_param<T: DeserializeOwned>(param: Option<&str>) -> anyhow::Result<T> {
+    let param = param.map(|v| v.trim()).unwrap_or_default();
+    if param.is_empty() {
+        return serde_json::from_value(Value::Null)
+            .map_err(|e| anyhow::anyhow!("job param is empty, {}", e));
+    }
+    //按参数格式确定错误原因：像json时使用json的解析错误，包含`=`时使用查询字符串的解析错误
+    let mut format_err = None;
+    match serde_json::from_str(param) {
+        Ok(v) => return Ok(v),
+        Err(e) => {
+            if param.starts_with('{') || param.starts_with('[') {
+                format_err = Some(e.to_string());
+            }
+        }
+    }
+    if param.contains('=') {
+        let query = param
+            .split(|c: char| c == '&' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<&str>>()
+            .join("&");
+        match serde_urlencoded::from_str(&query) {
+            Ok(v) => return Ok(v),
+            Err(e) => {
+                format_err.get_or_insert(e.to_string());
+            }
+        }
+    }
+    let args: Vec<&str> = param.split_whitespace().collect();
+    let values: Vec<Value> = args.iter().map(|v| arg_value(v)).collect();
+    let mut candidates = vec![];
+    if values.len() == 1 {
+        candidates.push(values[0].clone());
+    }
+    candidates.push(Value::Array(values));
+    candidates.push(Value::Array(
+        args.iter().map(|v| Value::String(v.to_string())).collect(),
+    ));
+    candidates.push(Value::String(param.to_owned()));
+    let mut last_err = String::new();
+    for value in candidates {
+        match serde_json::from_value(value) {
+            Ok(v) => return Ok(v),
+            Err(e) => last_err = e.to_string(),
+        }
+    }
+    let last_err = format_err.unwrap_or(last_err);
+    Err(anyhow::anyhow!(
+        "job param parse error:{}, param:{}",
+        last_err,
+        param
+    ))
+}
+
+/// 数字与布尔值按对应类型解析，其它按字符串处理
+fn arg_value(arg: &str) -> Value {
+    match serde_json::from_str::<Value>(arg) {
+        Ok(v @ (Value::Number(_) | Value::Bool(_))) => v,
+        _ => Value::String(arg.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Param {
+        id: u64,
+        name: String,
+    }
+
+    #[test]
+    fn number_param_as_string() {
+        let v: String = parse_job_param(Some("123")).unwrap();
+        assert_eq!(v, "123");
+        let v: u64 = parse_job_param(Some("123")).unwrap();
+        assert_eq!(v, 123);
+    }
+
+    #[test]
+    fn query_param_as_string() {
+        let v: String = parse_job_param(Some("a=b")).unwrap();
+        assert_eq!(v, "a=b");
+        let v: Param = parse_job_param(Some("id=1&name=a")).unwrap();
+        assert_eq!(
+            v,
+            Param {
+                id: 1,
+                name: "a".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn empty_param() {
+        let v: Option<String> = parse_job_param(None).unwrap();
+        assert_eq!(v, None);
+        let v: Option<Param> = parse_job_param(Some("  ")).unwrap();
+        assert_eq!(v, None);
+        let err = parse_job_param::<String>(Some("")).unwrap_err();
+        assert!(err.to_string().starts_with("job param is empty"));
+        assert!(parse_job_param::<Param>(None).is_err());
+    }
+
+    #[test]
+    fn malformed_param() {
+        let err = parse_job_param::<Param>(Some("id=abc&name=a")).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.starts_with("job param parse error:"), "{}", msg);
+        assert!(msg.ends_with("param:id=abc&name=a"), "{}", msg);
+        assert!(msg.contains("invalid digit found in string"), "{}", msg);
+
+        let err = parse_job_param::<Param>(Some(r#"{"id":"x","name":"a"}"#)).unwrap_err();
+        let msg = err.to_string();
+        assert!(
+            msg.contains(r#"invalid type: string "x", expected u64"#),
+            "{}",
+            msg
+        );
+
+        let err = parse_job_param::<(u64, u64)>(Some("1 a")).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("expected a tuple of size 2"), "{}", msg);
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+/// 服务端分页查询结果
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PageResult<T> {
+    pub records_total: u64,
+    pub records_filtered: u64,
+    pub data: Vec<T>,
+}
+
+/// 执行器
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct XxlJobGroup {
+    pub id: i32,
+    pub appname: String,
+    pub title: String,
+    /// 0:自动注册,1:手动录入
+    pub address_type: i32,
+    /// 手动录入的执行器地址，多个用逗号分隔
+    pub address_list: Option<String>,
+    #[serde(skip_serializing)]
+    pub update_time: Option<serde_json::Value>,
+    #[serde(skip_serializing)]
+    pub registry_list: Option<Vec<String>>,
+}
+
+/// 任务
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct XxlJobInfo {
+    pub id: u64,
+    pub job_group: i32,
+    pub job_desc: String,
+    pub author: String,
+    pub alarm_email: Option<String>,
+    /// NONE,CRON,FIX_RATE
+    pub schedule_type: String,
+    pub schedule_conf: Option<String>,
+    /// DO_NOTHING,FIRE_ONCE_NOW
+    pub misfire_strategy: String,
+    /// FIRST,LAST,ROUND,RANDOM,CONSISTENT_HASH,LEAST_FREQUENTLY_USED,LEAST_RECENTLY_USED,FAILOVER,BUSYOVER,SHARDING_BROADCAST
+    pub executor_route_strategy: String,
+    pub executor_handler: Option<String>,
+    pub executor_param: Option<String>,
+    pub executor_block_strategy: String,
+    pub executor_timeout: i32,
+    pub executor_fail_retry_count: i32,
+    pub glue_type: String,
+    pub glue_source: Option<String>,
+    pub glue_remark: Option<String>,
+    pub child_job_id: Option<String>,
+    /// 0:停止,1:运行
+    #[serde(skip_serializing)]
+    pub trigger_status: i32,
+    #[serde(skip_serializing)]
+    pub trigger_last_time: i64,
+    #[serde(skip_serializing)]
+    pub trigger_next_time: i64,
+    #[serde(skip_serializing)]
+    pub add_time: Option<serde_json::Value>,
+    #[serde(skip_serializing)]
+    pub update_time: Option<serde_json::Value>,
+    #[serde(skip_serializing, rename(deserialize = "glueUpdatetime"))]
+    pub glue_update_time: Option<serde_json::Value>,
+}
+
+impl Default for XxlJobInfo {
+    /// 与服务端新增任务页面的默认值一致
+    fn default() -> Self {
+        Self {
+            id: 0,
+            job_group: 0,
+            job_desc: String::new(),
+            author: String::new(),
+            alarm_email: None,
+            schedule_type: "NONE".to_owned(),
+            schedule_conf: None,
+            misfire_strategy: "DO_NOTHING".to_owned(),
+            executor_route_strategy: "FIRST".to_owned(),
+            executor_handler: None,
+            executor_param: None,
+            executor_block_strategy: "SERIAL_EXECUTION".to_owned(),
+            executor_timeout: 0,
+            executor_fail_retry_count: 0,
+            glue_type: "BEAN".to_owned(),
+            glue_source: None,
+            glue_remark: None,
+            child_job_id: None,
+            trigger_status: 0,
+            trigger_last_time: 0,
+            trigger_next_time: 0,
+            add_time: None,
+            update_time: None,
+            glue_update_time: None,
+        }
+    }
+}
+
+/// 调度日志
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct XxlJobLog {
+    pub id: u64,
+    pub job_group: i32,
+    pub job_id: u64,
+    pub executor_address: Option<String>,
+    pub executor_handler: Option<String>,
+    pub executor_param: Option<String>,
+    pub executor_sharding_param: Option<String>,
+    pub executor_fail_retry_count: i32,
+    pub trigger_time: Option<serde_json::Value>,
+    pub trigger_code: i32,
+    pub trigger_msg: Option<String>,
+    pub handle_time: Option<serde_json::Value>,
+    pub handle_code: i32,
+    pub handle_msg: Option<String>,
+    pub alarm_status: i32,
+}
+
+/// 执行器分页查询条件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobGroupQuery {
+    pub appname: Option<String>,
+    pub title: Option<String>,
+    pub start: u32,
+    pub length: u32,
+}
+
+impl Default for JobGroupQuery {
+    fn default() -> Self {
+        Self {
+            appname: None,
+            title: None,
+            start: 0,
+            length: 10,
+        }
+    }
+}
+
+/// 任务分页查询条件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfoQuery {
+    pub job_group: i32,
+    /// -1:全部,0:停止,1:运行
+    pub trigger_status: i32,
+    pub job_desc: Option<String>,
+    pub executor_handler: Option<String>,
+    pub author: Option<String>,
+    pub start: u32,
+    pub length: u32,
+}
+
+impl Default for JobInfoQuery {
+    fn default() -> Self {
+        Self {
+            job_group: 0,
+            trigger_status: -1,
+            job_desc: None,
+            executor_handler: None,
+            author: None,
+            start: 0,
+            length: 10,
+        }
+    }
+}
+
+/// 调度日志分页查询条件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobLogQuery {
+    pub job_group: i32,
+    /// 0表示全部任务
+    pub job_id: u64,
+    /// -1:全部,1:成功,2:失败,3:进行中
+    pub log_status: i32,
+    /// 格式：yyyy-MM-dd HH:mm:ss - yyyy-MM-dd HH:mm:ss
+    pub filter_time: Option<String>,
+    pub start: u32,
+    pub length: u32,
+}
+
+impl Default for JobLogQuery {
+    fn default() -> Self {
+        Self {
+            job_group: 0,
+            job_id: 0,
+            log_status: -1,
+            filter_time: None,
+            start: 0,
+            length: 10,
+        }
+    }
+}
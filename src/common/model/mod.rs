@@ -2,11 +2,15 @@ pub mod admin_request;
 pub mod api_model;
 pub mod enum_type;
 pub mod handler;
+pub mod interceptor;
+pub mod job_param;
+pub mod manage_model;
 
 use serde::{Deserialize, Serialize};
 
 pub const SUCCESS_CODE: i32 = 200;
 pub const FAIL_CODE: i32 = 500;
+pub const TIMEOUT_CODE: i32 = 502;
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct XxlApiResult<T>
@@ -0,0 +1,313 @@
+use futures_util::{future, Stream, StreamExt};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// 一致性hash每个分片默认的虚拟节点数量
+pub const DEFAULT_VIRTUAL_NODES: usize = 160;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 稳定的hash(FNV-1a 64位)；
+/// 不同进程、不同版本的计算结果一致，分片结果不会因为执行器重启而变化；
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// 打散hash值，让相近的key在hash环上均匀分布(murmur3 fmix64)
+fn mix_hash(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    hash
+}
+
+/// 用于分片的key；
+/// 整数直接使用数值(按id取模)，字符串与字节数组使用`stable_hash`；
+pub trait ShardKey {
+    fn shard_hash(&self) -> u64;
+}
+
+macro_rules! impl_int_shard_key {
+    ($($t:ty),*) => {
+        $(
+            impl ShardKey for $t {
+                fn shard_hash(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_int_shard_key!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl ShardKey for str {
+    fn shard_hash(&self) -> u64 {
+        stable_hash(self.as_bytes())
+    }
+}
+
+impl ShardKey for String {
+    fn shard_hash(&self) -> u64 {
+        stable_hash(self.as_bytes())
+    }
+}
+
+impl ShardKey for [u8] {
+    fn shard_hash(&self) -> u64 {
+        stable_hash(self)
+    }
+}
+
+impl ShardKey for Vec<u8> {
+    fn shard_hash(&self) -> u64 {
+        stable_hash(self)
+    }
+}
+
+impl<T: ShardKey + ?Sized> ShardKey for &T {
+    fn shard_hash(&self) -> u64 {
+        (**self).shard_hash()
+    }
+}
+
+/// 分片规则，计算key所属的分片序号
+pub trait Partitioner: Send + Sync {
+    fn shard_of(&self, key_hash: u64) -> u64;
+}
+
+/// 取模分片；分片总数变化时大部分key会换分片
+#[derive(Clone, Debug)]
+pub struct ModuloPartitioner {
+    total: u64,
+}
+
+impl ModuloPartitioner {
+    pub fn new(total: u64) -> Self {
+        Self {
+            total: total.max(1),
+        }
+    }
+}
+
+impl Partitioner for ModuloPartitioner {
+    fn shard_of(&self, key_hash: u64) -> u64 {
+        key_hash % self.total
+    }
+}
+
+/// 一致性hash分片；分片总数变化时只有少部分key会换分片，适合分片上有本地缓存或状态的任务
+#[derive(Clone, Debug)]
+pub struct ConsistentHashPartitioner {
+    ring: Arc<Vec<(u64, u64)>>,
+}
+
+impl ConsistentHashPartitioner {
+    pub fn new(total: u64) -> Self {
+        Self::with_virtual_nodes(total, DEFAULT_VIRTUAL_NODES)
+    }
+
+    pub fn with_virtual_nodes(total: u64, virtual_nodes: usize) -> Self {
+        let virtual_nodes = virtual_nodes.max(1);
+        let mut ring = Vec::with_capacity(total.max(1) as usize * virtual_nodes);
+        for shard in 0..total.max(1) {
+            for node in 0..virtual_nodes {
+                let point = mix_hash(stable_hash(format!("shard-{}#{}", shard, node).as_bytes()));
+                ring.push((point, shard));
+            }
+        }
+        ring.sort_unstable();
+        Self {
+            ring: Arc::new(ring),
+        }
+    }
+}
+
+impl Partitioner for ConsistentHashPartitioner {
+    fn shard_of(&self, key_hash: u64) -> u64 {
+        let point = mix_hash(key_hash);
+        let i = self.ring.partition_point(|(v, _)| *v < point);
+        self.ring
+            .get(i)
+            .or(self.ring.first())
+            .map(|v| v.1)
+            .unwrap_or(0)
+    }
+}
+
+/// 当前执行器的分片；
+/// 广播任务中每个执行器的shard_index不同，通过`is_mine`判断数据是否由当前分片处理；
+///
+/// ```ignore
+/// let sharding = context.sharding();
+/// for id in sharding.id_range(0..1_000_000) { /* 连续id区间 */ }
+/// let users = sharding.filter(users, |user| user.id);
+/// let ring = context.sharding().consistent_hash();
+/// if ring.is_mine("user-1") { }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sharding<P = ModuloPartitioner> {
+    index: u64,
+    total: u64,
+    partitioner: P,
+}
+
+impl Sharding<ModuloPartitioner> {
+    /// 分片总数为0时按1处理
+    pub fn new(index: u64, total: u64) -> Self {
+        let total = total.max(1);
+        Self {
+            index,
+            total,
+            partitioner: ModuloPartitioner::new(total),
+        }
+    }
+}
+
+impl<P: Partitioner> Sharding<P> {
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// 改为使用一致性hash分片
+    pub fn consistent_hash(self) -> Sharding<ConsistentHashPartitioner> {
+        let partitioner = ConsistentHashPartitioner::new(self.total);
+        self.with_partitioner(partitioner)
+    }
+
+    /// 使用自定义的分片规则
+    pub fn with_partitioner<Q: Partitioner>(self, partitioner: Q) -> Sharding<Q> {
+        Sharding {
+            index: self.index,
+            total: self.total,
+            partitioner,
+        }
+    }
+
+    /// key所属的分片序号
+    pub fn shard_of<K: ShardKey + ?Sized>(&self, key: &K) -> u64 {
+        self.partitioner.shard_of(key.shard_hash())
+    }
+
+    /// key是否由当前分片处理
+    pub fn is_mine<K: ShardKey + ?Sized>(&self, key: &K) -> bool {
+        self.shard_of(key) == self.index
+    }
+
+    /// 把[start, end)平均拆成total段连续区间，返回当前分片的区间；
+    /// 不能整除时前面的分片多分一个，分片序号超出范围时返回空区间；
+    pub fn id_range(&self, range: Range<u64>) -> Range<u64> {
+        if range.end <= range.start || self.index >= self.total {
+            return range.end..range.end;
+        }
+        let len = range.end - range.start;
+        let base = len / self.total;
+        let remainder = len % self.total;
+        let start = range.start + self.index * base + self.index.min(remainder);
+        let size = base + u64::from(self.index < remainder);
+        start..start + size
+    }
+
+    /// 过滤出由当前分片处理的元素
+    pub fn filter<I, F, K>(&self, iter: I, key_fn: F) -> impl Iterator<Item = I::Item>
+    where
+        I: IntoIterator,
+        F: Fn(&I::Item) -> K,
+        K: ShardKey,
+        P: Clone,
+    {
+        let sharding = self.clone();
+        iter.into_iter()
+            .filter(move |item| sharding.is_mine(&key_fn(item)))
+    }
+
+    /// 过滤出由当前分片处理的元素，用于异步流
+    pub fn filter_stream<S, F, K>(&self, stream: S, key_fn: F) -> impl Stream<Item = S::Item>
+    where
+        S: Stream,
+        F: Fn(&S::Item) -> K,
+        K: ShardKey,
+        P: Clone,
+    {
+        let sharding = self.clone();
+        stream.filter(move |item| future::ready(sharding.is_mine(&key_fn(item))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id_range_split_remainder() {
+        //10个id分3片，前面的分片多分一个
+        let ranges: Vec<Range<u64>> = (0..3)
+            .map(|index| Sharding::new(index, 3).id_range(100..110))
+            .collect();
+        assert_eq!(ranges, vec![100..104, 104..107, 107..110]);
+
+        //id数量少于分片数量时，后面的分片为空区间
+        let ranges: Vec<Range<u64>> = (0..4)
+            .map(|index| Sharding::new(index, 4).id_range(0..2))
+            .collect();
+        assert_eq!(ranges, vec![0..1, 1..2, 2..2, 2..2]);
+    }
+
+    #[test]
+    fn id_range_index_out_of_range() {
+        assert!(Sharding::new(3, 3).id_range(0..100).is_empty());
+        assert!(Sharding::new(0, 3).id_range(10..10).is_empty());
+        //分片总数为0时按1处理
+        assert_eq!(Sharding::new(0, 0).id_range(0..100), 0..100);
+    }
+
+    #[test]
+    fn consistent_hash_stable_when_total_changes() {
+        let before = Sharding::new(0, 10).consistent_hash();
+        let after = Sharding::new(0, 11).consistent_hash();
+        let mut moved = 0;
+        for key in 0..10_000u64 {
+            let old_shard = before.shard_of(&key);
+            let new_shard = after.shard_of(&key);
+            assert!(old_shard < 10);
+            if old_shard != new_shard {
+                //增加分片时，key只会移动到新增的分片
+                assert_eq!(new_shard, 10);
+                moved += 1;
+            }
+        }
+        assert!(moved > 0 && moved < 2_000, "moved:{}", moved);
+        //相同参数重新构造时分片结果不变
+        let rebuilt = Sharding::new(0, 10).consistent_hash();
+        assert!((0..1_000u64).all(|key| before.shard_of(&key) == rebuilt.shard_of(&key)));
+    }
+
+    #[test]
+    fn negative_int_keys() {
+        let sharding = Sharding::new(0, 7);
+        for key in [-1i64, -5, -100, i64::MIN] {
+            let shard = sharding.shard_of(&key);
+            assert!(shard < 7);
+            //不同整数类型的相同负数属于同一个分片
+            if let Ok(v) = i32::try_from(key) {
+                assert_eq!(sharding.shard_of(&v), shard);
+                assert_eq!(sharding.shard_of(&(v as isize)), shard);
+            }
+        }
+        let ring = sharding.consistent_hash();
+        assert_eq!(ring.shard_of(&-5i32), ring.shard_of(&-5i64));
+        assert!(ring.shard_of(&i64::MIN) < 7);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::common::client_config::ClientConfig;
+use crate::common::client_state::ClientState;
 use crate::executor::admin_server::ServerAccessActor;
 use crate::executor::core::ExecutorActor;
+use crate::server::web_server::ServerRunner;
 use actix::Addr;
 use std::sync::Arc;
 
@@ -8,5 +10,7 @@ use std::sync::Arc;
 pub struct ShareData {
     pub executor_actor: Addr<ExecutorActor>,
     pub server_access_actor: Addr<ServerAccessActor>,
+    pub server_runner: Addr<ServerRunner>,
     pub client_config: Arc<ClientConfig>,
+    pub client_state: Arc<ClientState>,
 }
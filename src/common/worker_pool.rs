@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Clone, Debug)]
+pub struct WorkerPoolConfig {
+    /// 最大线程数
+    pub max_threads: usize,
+    /// 线程都在运行时最多排队的任务数
+    pub queue_size: usize,
+    /// 线程名前缀
+    pub thread_name: Arc<String>,
+    /// 空闲线程保留时间
+    pub keep_alive: Duration,
+}
+
+impl Default for WorkerPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_threads: 200,
+            queue_size: 1000,
+            thread_name: Arc::new("xxljob-sync-worker".to_owned()),
+            keep_alive: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PoolState {
+    queue: VecDeque<Task>,
+    threads: usize,
+    idle: usize,
+    thread_seq: usize,
+}
+
+struct PoolInner {
+    config: WorkerPoolConfig,
+    state: Mutex<PoolState>,
+    condvar: Condvar,
+}
+
+impl PoolInner {
+    /// 任务在锁外运行，锁中毒时状态仍然一致，直接恢复使用，避免线程计数泄漏
+    fn lock_state(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 有界线程池；
+/// 线程按需创建，空闲超过keep_alive后退出；
+/// 线程数达到上限且排队已满时拒绝新任务；
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<PoolInner>,
+}
+
+impl Default for WorkerPool {
+    fn default() -> Self {
+        Self::new(WorkerPoolConfig::default())
+    }
+}
+
+impl WorkerPool {
+    pub fn new(config: WorkerPoolConfig) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                config,
+                state: Mutex::new(PoolState::default()),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &WorkerPoolConfig {
+        &self.inner.config
+    }
+
+    /// 提交任务，线程池已满时返回错误
+    pub fn execute<F>(&self, task: F) -> anyhow::Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let config = &self.inner.config;
+        let mut state = self.inner.lock_state();
+        if state.queue.len() < state.idle {
+            state.queue.push_back(Box::new(task));
+            self.inner.condvar.notify_one();
+            return Ok(());
+        }
+        if state.threads < config.max_threads.max(1) {
+            state.threads += 1;
+            state.thread_seq += 1;
+            let thread_name = format!("{}-{}", &config.thread_name, state.thread_seq);
+            let inner = self.inner.clone();
+            let first_task: Task = Box::new(task);
+            if let Err(e) = std::thread::Builder::new()
+                .name(thread_name)
+                .spawn(move || Self::run_worker(inner, first_task))
+            {
+                state.threads -= 1;
+                return Err(anyhow::anyhow!("worker pool spawn thread error:{}", e));
+            }
+            return Ok(());
+        }
+        if state.queue.len() - state.idle < config.queue_size {
+            state.queue.push_back(Box::new(task));
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "worker pool is full, max_threads:{}, queue_size:{}",
+            config.max_threads,
+            config.queue_size
+        ))
+    }
+
+    fn run_worker(inner: Arc<PoolInner>, first_task: Task) {
+        let mut task = Some(first_task);
+        while let Some(current) = task.take() {
+            //任务panic不影响线程复用
+            if std::panic::catch_unwind(AssertUnwindSafe(current)).is_err() {
+                log::error!("worker pool task panicked");
+            }
+            task = Self::next_task(&inner);
+        }
+    }
+
+    fn next_task(inner: &PoolInner) -> Option<Task> {
+        let mut state = inner.lock_state();
+        loop {
+            if let Some(task) = state.queue.pop_front() {
+                return Some(task);
+            }
+            state.idle += 1;
+            let (new_state, wait_result) = inner
+                .condvar
+                .wait_timeout(state, inner.config.keep_alive)
+                .unwrap_or_else(|e| e.into_inner());
+            state = new_state;
+            state.idle -= 1;
+            if wait_result.timed_out() && state.queue.is_empty() {
+                state.threads -= 1;
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn pool(max_threads: usize, queue_size: usize, keep_alive: Duration) -> WorkerPool {
+        WorkerPool::new(WorkerPoolConfig {
+            max_threads,
+            queue_size,
+            thread_name: Arc::new("test-worker".to_owned()),
+            keep_alive,
+        })
+    }
+
+    fn threads(pool: &WorkerPool) -> usize {
+        pool.inner.lock_state().threads
+    }
+
+    #[test]
+    fn reject_when_full() {
+        let pool = pool(1, 1, Duration::from_secs(60));
+        let (block_tx, block_rx) = mpsc::channel::<()>();
+        let (done_tx, done_rx) = mpsc::channel::<u32>();
+        let tx = done_tx.clone();
+        pool.execute(move || {
+            block_rx.recv().ok();
+            tx.send(1).ok();
+        })
+        .unwrap();
+        let tx = done_tx.clone();
+        pool.execute(move || {
+            tx.send(2).ok();
+        })
+        .unwrap();
+        //线程与排队都已占满
+        let err = pool.execute(|| {}).unwrap_err();
+        assert!(
+            err.to_string().starts_with("worker pool is full"),
+            "{}",
+            err
+        );
+
+        block_tx.send(()).unwrap();
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(1)).unwrap(), 1);
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(1)).unwrap(), 2);
+        assert_eq!(threads(&pool), 1);
+    }
+
+    #[test]
+    fn idle_thread_exit_after_keep_alive() {
+        let pool = pool(2, 0, Duration::from_millis(50));
+        let (tx, rx) = mpsc::channel::<()>();
+        for _ in 0..2 {
+            let tx = tx.clone();
+            pool.execute(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                tx.send(()).ok();
+            })
+            .unwrap();
+        }
+        for _ in 0..2 {
+            rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        }
+        assert_eq!(threads(&pool), 2);
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(threads(&pool), 0);
+        //线程退出后可以重新创建
+        let tx = tx.clone();
+        pool.execute(move || {
+            tx.send(()).ok();
+        })
+        .unwrap();
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    }
+
+    #[test]
+    fn panic_task_not_kill_worker() {
+        let pool = pool(1, 10, Duration::from_secs(60));
+        let (tx, rx) = mpsc::channel::<()>();
+        pool.execute(|| panic!("test panic")).unwrap();
+        pool.execute(move || {
+            tx.send(()).ok();
+        })
+        .unwrap();
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(threads(&pool), 1);
+    }
+
+    #[test]
+    fn recover_poisoned_lock() {
+        let pool = pool(1, 10, Duration::from_millis(50));
+        let inner = pool.inner.clone();
+        std::thread::spawn(move || {
+            let _state = inner.state.lock().unwrap();
+            panic!("poison worker pool lock");
+        })
+        .join()
+        .ok();
+        assert!(pool.inner.state.is_poisoned());
+        let (tx, rx) = mpsc::channel::<()>();
+        pool.execute(move || {
+            tx.send(()).ok();
+        })
+        .unwrap();
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        //空闲线程超时后正常退出，线程计数不泄漏
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(threads(&pool), 0);
+    }
+}
@@ -1,11 +1,14 @@
 use crate::client::admin_client::AdminClient;
 use crate::common::client_config::ClientConfig;
+use crate::common::client_state::ClientState;
 use crate::common::model::admin_request::CallbackParam;
 use crate::common::model::SUCCESS_CODE;
 use crate::common::now_millis_i64;
+use crate::executor::callback_log::CallbackLogStore;
 use crate::executor::model::{ServerAccessActorReq, ServerAccessActorResult};
 use actix::prelude::*;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use rand::Rng;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -13,14 +16,55 @@ use std::time::Duration;
 pub struct ServerAccessActor {
     admin_client: Arc<AdminClient>,
     running: bool,
+    pending_callback_count: usize,
+    callback_log: Arc<CallbackLogStore>,
+    retrying_callback_log: bool,
+    callback_queue: Vec<CallbackParam>,
+    callback_flush_scheduled: bool,
+    client_config: Arc<ClientConfig>,
+    client_state: Arc<ClientState>,
 }
 
 impl ServerAccessActor {
-    pub fn new(client_config: Arc<ClientConfig>) -> Self {
-        let admin_client = Arc::new(AdminClient::new(client_config).unwrap());
+    pub fn new(client_config: Arc<ClientConfig>, client_state: Arc<ClientState>) -> Self {
+        let callback_log = Arc::new(CallbackLogStore::new(&client_config.log_path));
+        let admin_client =
+            Arc::new(AdminClient::new(client_config.clone(), client_state.clone()).unwrap());
         Self {
             admin_client,
             running: false,
+            pending_callback_count: 0,
+            callback_log,
+            retrying_callback_log: false,
+            callback_queue: Vec::new(),
+            callback_flush_scheduled: false,
+            client_config,
+            client_state,
+        }
+    }
+
+    /// 任务结果先放入队列，达到批量大小或时间窗口后再回调
+    fn push_callback(&mut self, params: Vec<CallbackParam>, ctx: &mut Context<Self>) {
+        self.callback_queue.extend(params);
+        if self.callback_queue.len() >= self.client_config.callback_batch_size {
+            self.flush_callback(ctx);
+        } else if !self.callback_flush_scheduled {
+            self.callback_flush_scheduled = true;
+            ctx.run_later(self.client_config.callback_batch_window, |act, ctx| {
+                act.callback_flush_scheduled = false;
+                act.flush_callback(ctx);
+            });
+        }
+    }
+
+    fn flush_callback(&mut self, ctx: &mut Context<Self>) {
+        let batch_size = self.client_config.callback_batch_size;
+        while !self.callback_queue.is_empty() {
+            let size = batch_size.min(self.callback_queue.len());
+            let params: Vec<CallbackParam> = self.callback_queue.drain(..size).collect();
+            self.client_state.record_callback_batch(params.len());
+            log::debug!("callback batch size:{}", params.len());
+            self.callback(params, ctx);
         }
     }
 
@@ -28,17 +72,38 @@ impl ServerAccessActor {
         if !self.running {
             return;
         }
-        ctx.run_later(Duration::from_millis(29500), |act, ctx| {
-            act.do_heartbeat(ctx);
-            act.registry_heartbeat(ctx);
+        let interval = self.client_config.registry_heartbeat_interval + self.random_jitter();
+        ctx.run_later(interval, |act, ctx| {
+            if act.running {
+                act.do_heartbeat(ctx);
+                act.registry_heartbeat(ctx);
+            }
         });
     }
 
+    fn random_jitter(&self) -> Duration {
+        let jitter = self.client_config.registry_jitter.as_millis() as u64;
+        if jitter == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(rand::thread_rng().gen_range(0..jitter))
+        }
+    }
+
     fn do_heartbeat(&self, ctx: &mut Context<Self>) {
         let client = self.admin_client.clone();
         async move { client.registry().await }
             .into_actor(self)
-            .map(|_res, _act, _ctx| {})
+            .map(|res, act, _ctx| {
+                //注销后返回的心跳结果不再更新状态
+                if !act.running {
+                    return;
+                }
+                match res {
+                    Ok(_) => act.client_state.record_registry_success(),
+                    Err(e) => act.client_state.record_registry_failure(e.to_string()),
+                }
+            })
             .spawn(ctx);
     }
 
@@ -48,23 +113,103 @@ impl ServerAccessActor {
             .into_actor(self)
             .map(|_res, act, ctx| {
                 act.running = false;
+                act.client_state.record_registry_removed();
                 ctx.stop()
             })
             .wait(ctx);
     }
 
-    fn callback(&self, params: Vec<CallbackParam>, ctx: &mut Context<Self>) {
+    fn callback(&mut self, params: Vec<CallbackParam>, ctx: &mut Context<Self>) {
+        let client = self.admin_client.clone();
+        let callback_log = self.callback_log.clone();
+        let retry_delays = self.client_config.callback_retry_delays.clone();
+        let client_state = self.client_state.clone();
+        self.pending_callback_count += 1;
+        async move {
+            let metrics = client_state.metrics();
+            if callback_log.is_enabled() {
+                //失败后保存到本地，由后台定时重试
+                if client.callback(&params).await.is_err() {
+                    metrics.record_callback_failure();
+                    if let Err(e) = callback_log.save(&params).await {
+                        log::error!("save callback log error:{}", e);
+                    }
+                }
+                return;
+            }
+            //失败后按重试间隔依次重试
+            for delay in retry_delays.iter() {
+                if client.callback(&params).await.is_ok() {
+                    return;
+                }
+                metrics.record_callback_failure();
+                tokio::time::sleep(*delay).await;
+                metrics.record_callback_retry();
+            }
+            if client.callback(&params).await.is_err() {
+                metrics.record_callback_failure();
+            }
+        }
+        .into_actor(self)
+        .map(|_res, act, _ctx| {
+            act.pending_callback_count -= 1;
+        })
+        .spawn(ctx);
+    }
+}
+
+impl ServerAccessActor {
+    fn callback_log_heartbeat(&mut self, ctx: &mut Context<Self>) {
+        if !self.callback_log.is_enabled() {
+            return;
+        }
+        self.retry_callback_log(ctx);
+        ctx.run_later(
+            self.client_config.callback_log_retry_interval,
+            |act, ctx| {
+                act.callback_log_heartbeat(ctx);
+            },
+        );
+    }
+
+    /// 重试保存在本地的回调，启动时与之后每隔callback_log_retry_interval执行一次
+    fn retry_callback_log(&mut self, ctx: &mut Context<Self>) {
+        if self.retrying_callback_log {
+            return;
+        }
+        self.retrying_callback_log = true;
         let client = self.admin_client.clone();
+        let callback_log = self.callback_log.clone();
+        let client_state = self.client_state.clone();
         async move {
-            let mut i = 2u16;
-            //失败最多尝试重试10次
-            while i < 12u16 && client.callback(&params).await.is_err() {
-                tokio::time::sleep(Duration::from_secs((i * i) as u64)).await;
-                i += 1;
+            loop {
+                let params = match callback_log.load(100).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("load callback log error:{}", e);
+                        break;
+                    }
+                };
+                if params.is_empty() {
+                    break;
+                }
+                client_state.metrics().record_callback_retry();
+                if client.callback(&params).await.is_err() {
+                    client_state.metrics().record_callback_failure();
+                    break;
+                }
+                log::info!("retry callback log success, count:{}", params.len());
+                //删除失败时会重复发送同一批记录，等下次重试
+                if let Err(e) = callback_log.remove(&params).await {
+                    log::error!("remove callback log error:{}", e);
+                    break;
+                }
             }
         }
         .into_actor(self)
-        .map(|_res, _act, _ctx| {})
+        .map(|_res, act, _ctx| {
+            act.retrying_callback_log = false;
+        })
         .spawn(ctx);
     }
 }
@@ -99,26 +244,61 @@ impl Inject for ServerAccessActor {
     ) {
         log::info!("ServerAccessActor inject");
         self.running = true;
-        self.do_heartbeat(ctx);
-        self.registry_heartbeat(ctx);
+        let first_delay = self.random_jitter();
+        if first_delay.is_zero() {
+            self.do_heartbeat(ctx);
+            self.registry_heartbeat(ctx);
+        } else {
+            ctx.run_later(first_delay, |act, ctx| {
+                if act.running {
+                    act.do_heartbeat(ctx);
+                    act.registry_heartbeat(ctx);
+                }
+            });
+        }
+        self.callback_log_heartbeat(ctx);
     }
 }
 
 impl Supervised for ServerAccessActor {}
 
 impl Handler<ServerAccessActorReq> for ServerAccessActor {
-    type Result = anyhow::Result<ServerAccessActorResult>;
+    type Result = ResponseActFuture<Self, anyhow::Result<ServerAccessActorResult>>;
 
     fn handle(&mut self, msg: ServerAccessActorReq, ctx: &mut Self::Context) -> Self::Result {
-        match msg {
+        let result = match msg {
             ServerAccessActorReq::Stop => {
                 self.stop(ctx);
+                ServerAccessActorResult::None
             }
             ServerAccessActorReq::CallBack(params) => {
-                self.callback(params, ctx);
+                self.push_callback(params, ctx);
+                ServerAccessActorResult::None
+            }
+            ServerAccessActorReq::RegistryRemove => {
+                //停止心跳后注销，注销完成后再返回
+                self.running = false;
+                let client = self.admin_client.clone();
+                return Box::pin(
+                    async move { client.registry_remove().await }
+                        .into_actor(self)
+                        .map(|res, act, _ctx| {
+                            act.client_state.record_registry_removed();
+                            res.map(|_| ServerAccessActorResult::None)
+                        }),
+                );
+            }
+            ServerAccessActorReq::QueryPendingCallbacks => {
+                ServerAccessActorResult::PendingCallbacks(
+                    self.pending_callback_count + self.callback_queue.len(),
+                )
             }
+            ServerAccessActorReq::QueryStatus => ServerAccessActorResult::Status {
+                pending_callbacks: self.pending_callback_count + self.callback_queue.len(),
+                admin_addrs: self.admin_client.addr_status(),
+            },
         };
-        Ok(ServerAccessActorResult::None)
+        Box::pin(actix::fut::ready(Ok(result)))
     }
 }
 
@@ -0,0 +1,160 @@
+use crate::common::model::admin_request::CallbackParam;
+use std::path::{Path, PathBuf};
+
+/// 回调失败记录，与java执行器一样保存在{log_path}/callbacklog目录；
+/// 每个log_id一个文件，同一log_id只保留最后一次的结果；
+/// 没有设置log_path时不保存；
+#[derive(Clone, Debug, Default)]
+pub struct CallbackLogStore {
+    dir: Option<PathBuf>,
+}
+
+impl CallbackLogStore {
+    pub fn new(log_path: &str) -> Self {
+        let dir = if log_path.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(log_path).join("callbacklog"))
+        };
+        Self { dir }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    fn file_name(dir: &Path, log_id: u64) -> PathBuf {
+        dir.join(format!("xxl-job-callback-{}.log", log_id))
+    }
+
+    /// 保存回调失败记录
+    pub async fn save(&self, params: &[CallbackParam]) -> anyhow::Result<()> {
+        let dir = match &self.dir {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        tokio::fs::create_dir_all(dir).await?;
+        for param in params {
+            let file_name = Self::file_name(dir, param.log_id);
+            //先写临时文件再改名，避免重启时读到写了一半的记录
+            let tmp_file_name = file_name.with_extension("tmp");
+            tokio::fs::write(&tmp_file_name, serde_json::to_vec(param)?).await?;
+            tokio::fs::rename(&tmp_file_name, &file_name).await?;
+        }
+        Ok(())
+    }
+
+    /// 加载最多limit条回调失败记录
+    pub async fn load(&self, limit: usize) -> anyhow::Result<Vec<CallbackParam>> {
+        let dir = match &self.dir {
+            Some(v) => v,
+            None => return Ok(vec![]),
+        };
+        let mut params = vec![];
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(v) => v,
+            Err(_) => return Ok(params),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            if params.len() >= limit {
+                break;
+            }
+            let path = entry.path();
+            if path.extension().and_then(|v| v.to_str()) != Some("log") {
+                continue;
+            }
+            let content = tokio::fs::read(&path).await?;
+            match serde_json::from_slice::<CallbackParam>(&content) {
+                Ok(param) => params.push(param),
+                Err(e) => {
+                    log::error!("invalid callback log file:{:?},error:{}", &path, e);
+                    tokio::fs::remove_file(&path).await.ok();
+                }
+            }
+        }
+        Ok(params)
+    }
+
+    /// 回调成功后删除记录，记录已不存在时忽略
+    pub async fn remove(&self, params: &[CallbackParam]) -> anyhow::Result<()> {
+        if let Some(dir) = &self.dir {
+            for param in params {
+                match tokio::fs::remove_file(Self::file_name(dir, param.log_id)).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callback_param(log_id: u64, handle_code: i32) -> CallbackParam {
+        CallbackParam {
+            log_id,
+            log_date_tim: 0,
+            handle_code,
+            handle_msg: Some(format!("msg-{}", log_id)),
+        }
+    }
+
+    fn sorted(mut params: Vec<CallbackParam>) -> Vec<(u64, i32)> {
+        params.sort_by_key(|v| v.log_id);
+        params.iter().map(|v| (v.log_id, v.handle_code)).collect()
+    }
+
+    #[tokio::test]
+    async fn save_load_remove() {
+        let log_path = std::env::temp_dir().join(format!(
+            "xxljob-sdk-rs-callback-log-test-{}",
+            std::process::id()
+        ));
+        std::fs::remove_dir_all(&log_path).ok();
+        let store = CallbackLogStore::new(&log_path.to_string_lossy());
+        assert!(store.is_enabled());
+        assert!(store.load(10).await.unwrap().is_empty());
+
+        store
+            .save(&[callback_param(1, 500), callback_param(2, 500)])
+            .await
+            .unwrap();
+        //同一log_id只保留最后一次的结果
+        store.save(&[callback_param(1, 200)]).await.unwrap();
+        let params = store.load(10).await.unwrap();
+        assert_eq!(sorted(params.clone()), vec![(1, 200), (2, 500)]);
+        assert_eq!(
+            params[0].handle_msg,
+            Some(format!("msg-{}", params[0].log_id))
+        );
+        assert_eq!(store.load(1).await.unwrap().len(), 1);
+
+        //损坏的记录加载时删除
+        std::fs::write(
+            log_path.join("callbacklog").join("xxl-job-callback-3.log"),
+            b"{",
+        )
+        .unwrap();
+        assert_eq!(store.load(10).await.unwrap().len(), 2);
+
+        store.remove(&[callback_param(1, 200)]).await.unwrap();
+        assert_eq!(sorted(store.load(10).await.unwrap()), vec![(2, 500)]);
+        //重复删除不报错
+        store.remove(&[callback_param(1, 200)]).await.unwrap();
+        store.remove(&[callback_param(2, 500)]).await.unwrap();
+        assert!(store.load(10).await.unwrap().is_empty());
+        std::fs::remove_dir_all(&log_path).ok();
+    }
+
+    #[tokio::test]
+    async fn disabled_without_log_path() {
+        let store = CallbackLogStore::new("");
+        assert!(!store.is_enabled());
+        store.save(&[callback_param(1, 500)]).await.unwrap();
+        assert!(store.load(10).await.unwrap().is_empty());
+        store.remove(&[callback_param(1, 500)]).await.unwrap();
+    }
+}
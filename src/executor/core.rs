@@ -1,59 +1,172 @@
 #![allow(unused_variables, dead_code)]
 use crate::common::client_config::ClientConfig;
+use crate::common::client_state::{ClientState, JobHandlerStatus};
+use crate::common::constant::MIN_LOG_RETENTION_DAYS;
+use crate::common::job_log::{clean_expired_logs, write_job_log};
 use crate::common::model::enum_type::ExecutorBlockStrategy;
-use crate::common::model::handler::{JobContext, JobHandler, JobHandlerRunParam, JobHandlerValue};
-use crate::common::model::FAIL_CODE;
+use crate::common::model::handler::{
+    JobContext, JobHandleError, JobHandler, JobHandlerRunParam, JobHandlerValue, JobOutcome,
+};
+use crate::common::model::interceptor::JobInterceptor;
+use crate::common::model::{FAIL_CODE, SUCCESS_CODE, TIMEOUT_CODE};
+use crate::common::worker_pool::WorkerPool;
 use crate::executor::admin_server::{callback, ServerAccessActor};
+use crate::executor::glue::{glue_job_name, ScriptJobHandler};
 use crate::executor::model::{ExecutorActorReq, ExecutorActorResult};
 use actix::prelude::*;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Default)]
 #[bean(inject)]
 pub struct ExecutorActor {
     client_config: Arc<ClientConfig>,
+    client_state: Arc<ClientState>,
     job_handler_map: HashMap<Arc<String>, JobHandlerValue>,
     job_id_map: HashMap<u64, Arc<String>>,
+    glue_update_time_map: HashMap<u64, u64>,
+    sync_pool: WorkerPool,
     server_access_actor: Option<Addr<ServerAccessActor>>,
 }
 
 impl ExecutorActor {
-    pub fn new(client_config: Arc<ClientConfig>) -> Self {
+    pub fn new(client_config: Arc<ClientConfig>, client_state: Arc<ClientState>) -> Self {
+        let sync_pool = WorkerPool::new(client_config.sync_pool_config.clone());
         Self {
             client_config,
+            client_state,
             job_handler_map: HashMap::new(),
             job_id_map: HashMap::new(),
+            glue_update_time_map: HashMap::new(),
+            sync_pool,
             server_access_actor: None,
         }
     }
 
     fn register_job_handler(&mut self, job_handler: JobHandlerValue) {
-        self.job_handler_map
-            .insert(job_handler.name.clone(), job_handler);
+        if let Some(old_value) = self.job_handler_map.get_mut(&job_handler.name) {
+            //保留运行与排队状态，运行中的任务继续使用旧的任务处理器
+            log::warn!(
+                "job handler already registered, replace:{}",
+                &job_handler.name
+            );
+            old_value.handler = job_handler.handler;
+            old_value.config = job_handler.config;
+            old_value.draining = false;
+        } else {
+            self.job_handler_map
+                .insert(job_handler.name.clone(), job_handler);
+        }
+    }
+
+    fn replace_job_handler(
+        &mut self,
+        job_name: Arc<String>,
+        job_handler: JobHandler,
+    ) -> anyhow::Result<ExecutorActorResult> {
+        if let Some(value) = self
+            .job_handler_map
+            .get_mut(&job_name)
+            .filter(|v| !v.draining)
+        {
+            value.handler = job_handler;
+            log::info!("replace job handler:{}", &job_name);
+            Ok(ExecutorActorResult::Ok)
+        } else {
+            Err(anyhow::anyhow!(
+                "No handler registered for job:{}",
+                job_name.as_str()
+            ))
+        }
+    }
+
+    /// 移除任务处理器；运行中的任务继续执行完，排队中的任务按失败反馈；
+    /// 有运行中的任务时先标记为移除中，仍可终止与检测运行状态，任务都结束后再清理；
+    fn unregister_job_handler(
+        &mut self,
+        job_name: Arc<String>,
+    ) -> anyhow::Result<ExecutorActorResult> {
+        let value = if let Some(value) = self
+            .job_handler_map
+            .get_mut(&job_name)
+            .filter(|v| !v.draining)
+        {
+            value
+        } else {
+            return Err(anyhow::anyhow!(
+                "No handler registered for job:{}",
+                job_name.as_str()
+            ));
+        };
+        let reason = "job handler unregistered";
+        let removed_jobs: Vec<JobContext> = value.block_jobs.drain(..).collect();
+        let running_count = value.running_jobs.len();
+        value.draining = true;
+        self.client_state
+            .metrics()
+            .record_job_discard(&job_name, removed_jobs.len());
+        callback_removed_jobs(&removed_jobs, reason);
+        if running_count == 0 {
+            self.remove_job_handler(&job_name);
+        }
+        log::info!(
+            "unregister job handler:{}, running:{}, queued:{}",
+            &job_name,
+            running_count,
+            removed_jobs.len()
+        );
+        Ok(ExecutorActorResult::Ok)
+    }
+
+    /// 清理已移除注册的任务处理器
+    fn remove_job_handler(&mut self, job_name: &Arc<String>) {
+        self.job_handler_map.remove(job_name);
+        self.client_state.metrics().remove_handler(job_name);
+        self.job_id_map.retain(|_, name| name != job_name);
+        self.glue_update_time_map
+            .retain(|job_id, _| &glue_job_name(*job_id) != job_name);
     }
 
     fn run_job(
         &mut self,
         job_name: Arc<String>,
-        job_context: JobContext,
+        mut job_context: JobContext,
         ctx: &mut Context<Self>,
     ) -> anyhow::Result<ExecutorActorResult> {
-        self.job_id_map
-            .entry(job_context.job_id)
-            .or_insert_with(|| job_name.clone());
-        let run_param = if let Some(handler_value) = self.job_handler_map.get_mut(&job_name) {
-            if handler_value.is_running {
+        let run_param = if let Some(handler_value) = self
+            .job_handler_map
+            .get_mut(&job_name)
+            .filter(|v| !v.draining)
+        {
+            self.job_id_map.insert(job_context.job_id, job_name.clone());
+            if let Some(block_strategy) = &handler_value.config.block_strategy {
+                job_context.block_strategy = block_strategy.clone();
+            }
+            #[cfg(feature = "tracing")]
+            job_context
+                .span
+                .record("handler", job_name.as_str())
+                .record("block_strategy", job_context.block_strategy.to_str());
+            if handler_value.is_running() {
                 match &job_context.block_strategy {
                     ExecutorBlockStrategy::SerialExecution => {
-                        //如果超过排队上限会移除前面任务
-                        if let Some(old_job) = handler_value.push_block_job(job_context) {
-                            old_job.callback_failed();
+                        //如果超过排队上限，按配置移除最早的任务或拒绝新任务
+                        if let Some(overflow_job) = handler_value.push_block_job(job_context) {
+                            self.client_state.metrics().record_job_discard(&job_name, 1);
+                            overflow_job.callback_failed_with_info(
+                                format!(
+                                    "block strategy effect：Serial execution, job queue is full, capacity:{}",
+                                    handler_value.config.queue_capacity
+                                ),
+                                FAIL_CODE,
+                            );
                         }
                         return Ok(ExecutorActorResult::Ok);
                     }
                     ExecutorBlockStrategy::DiscardLater => {
+                        self.client_state.metrics().record_job_discard(&job_name, 1);
                         job_context.callback_failed_with_info(
                             format!(
                                 "Discard the job; job_id:{}, log_id:{}",
@@ -63,12 +176,25 @@ impl ExecutorActor {
                         );
                         return Ok(ExecutorActorResult::Discard);
                     }
-                    ExecutorBlockStrategy::CoverEarly | ExecutorBlockStrategy::Other => {}
+                    ExecutorBlockStrategy::CoverEarly => {
+                        //终止之前的任务，等之前的任务都结束后再运行
+                        let reason = format!(
+                            "block strategy effect：Cover Early, covered by log_id:{}",
+                            job_context.log_id
+                        );
+                        let (_, removed_jobs) = handler_value.kill_all_jobs(&reason);
+                        self.client_state
+                            .metrics()
+                            .record_job_discard(&job_name, removed_jobs.len());
+                        callback_removed_jobs(&removed_jobs, &reason);
+                        //只保留最新的覆盖任务，不受排队上限影响
+                        handler_value.block_jobs = vec![job_context];
+                        return Ok(ExecutorActorResult::Ok);
+                    }
+                    ExecutorBlockStrategy::Other => {}
                 }
             }
-            handler_value.is_running = true;
-            handler_value.last_run_id = job_context.log_id;
-            handler_value.build_run_param()
+            handler_value.start_job(&job_context)
         } else {
             return Err(anyhow::anyhow!(
                 "No handler registered for job:{}",
@@ -79,67 +205,140 @@ impl ExecutorActor {
         Ok(ExecutorActorResult::Ok)
     }
 
-    fn do_run_job(
+    fn run_glue_job(
         &mut self,
         job_context: JobContext,
+        glue_source: String,
+        glue_update_time: u64,
+        ctx: &mut Context<Self>,
+    ) -> anyhow::Result<ExecutorActorResult> {
+        let job_id = job_context.job_id;
+        let job_name = glue_job_name(job_id);
+        if self.glue_update_time_map.get(&job_id) != Some(&glue_update_time) {
+            //脚本有更新时终止旧版本任务
+            if let Some(mut old_value) = self.job_handler_map.remove(&job_name) {
+                let reason = "change job source or glue type";
+                let (_, removed_jobs) = old_value.kill_job(job_id, reason);
+                self.client_state
+                    .metrics()
+                    .record_job_discard(&job_name, removed_jobs.len());
+                callback_removed_jobs(&removed_jobs, reason);
+                self.client_state.metrics().remove_handler(&job_name);
+            }
+            let handler = ScriptJobHandler::new(
+                &self.client_config,
+                job_id,
+                job_context.glue_type.clone(),
+                glue_source,
+                glue_update_time,
+            );
+            self.register_job_handler(JobHandlerValue::new(
+                job_name.clone(),
+                JobHandler::Async(Arc::new(handler)),
+            ));
+            self.glue_update_time_map.insert(job_id, glue_update_time);
+        }
+        self.run_job(job_name, job_context, ctx)
+    }
+
+    fn do_run_job(
+        &mut self,
+        mut job_context: JobContext,
         job_handler_param: JobHandlerRunParam,
         ctx: &mut Context<Self>,
     ) {
         let job_handler = job_handler_param.handler.clone();
         let job_name = job_handler_param.name.clone();
         let log_id = job_context.log_id.to_owned();
+        let log_file_name = job_context.job_log_file_name.clone();
+        write_job_log(
+            log_file_name.as_deref(),
+            &format!(
+                "<br>----------- xxl-job job execute start -----------<br>----------- Param:{}",
+                job_context.job_param.as_deref().unwrap_or_default()
+            ),
+        );
+        job_context.start_timeout();
+        self.client_state.metrics().record_job_start(&job_name);
+        let start = Instant::now();
+        let sync_pool = self.sync_pool.clone();
+        #[cfg(feature = "tracing")]
+        let span = job_context.span.clone();
+        let interceptors: Vec<Arc<dyn JobInterceptor>> = self
+            .client_config
+            .interceptors
+            .iter()
+            .chain(job_handler_param.interceptors.iter())
+            .cloned()
+            .collect();
 
-        async move {
-            match job_handler {
-                JobHandler::Async(handler) => {
-                    (handler.process(job_context).await, job_name, log_id)
-                }
-                JobHandler::Sync(handler) => {
-                    let (tx, rx) = tokio::sync::oneshot::channel();
-                    std::thread::spawn(move || {
-                        let ctx = handler.process(job_context);
-                        tx.send(ctx).ok();
-                    });
-                    let res = match rx.await {
-                        Ok(v) => v,
-                        Err(e) => Err(anyhow::anyhow!(e)),
-                    };
-                    (res, job_name, log_id)
-                }
+        let client_state = self.client_state.clone();
+        let server_access_actor = self.server_access_actor.clone();
+        let (running_guard, mut running_guard_rx) = tokio::sync::mpsc::channel::<()>(1);
+        let process = async move {
+            let res = execute_job(
+                interceptors,
+                job_handler,
+                job_context,
+                sync_pool,
+                running_guard,
+            )
+            .await;
+            let (end_type, handle_code, handle_msg) = match &res {
+                Ok(outcome) => ("finish", outcome.handle_code(), outcome.handle_msg()),
+                Err(err) => (
+                    "error",
+                    JobHandleError::get_handle_code(err),
+                    Some(err.to_string()),
+                ),
+            };
+            write_job_log(
+                log_file_name.as_deref(),
+                &format!(
+                    "<br>----------- xxl-job job execute end({}) -----------<br>----------- Result: handleCode={}, handleMsg = {}",
+                    end_type,
+                    handle_code,
+                    handle_msg.as_deref().unwrap_or("null")
+                ),
+            );
+            #[cfg(feature = "tracing")]
+            tracing::info!(end_type, handle_code, "xxl job execute end");
+            client_state.metrics().record_job_finish(
+                &job_name,
+                handle_code == SUCCESS_CODE,
+                start.elapsed(),
+            );
+            if let Some(addr) = server_access_actor.as_ref() {
+                callback(addr, log_id, handle_code, handle_msg);
             }
-        }
-        .into_actor(self)
-        .map(|(r, job_name, log_id), act, ctx| {
-            match r {
-                Ok(job) => {
-                    job.callback_success();
-                }
-                Err(err) => {
-                    //失败时取不到job对象，通过job_id反馈结果
-                    if let Some(addr) = act.server_access_actor.as_ref() {
-                        callback(addr, log_id.to_owned(), FAIL_CODE, Some(err.to_string()));
+            //同步任务被终止或超时后线程可能还在运行，等线程结束后才标记任务结束
+            running_guard_rx.recv().await;
+            (job_name, log_id)
+        };
+        #[cfg(feature = "tracing")]
+        let process = tracing::Instrument::instrument(process, span);
+        process
+            .into_actor(self)
+            .map(|(job_name, log_id), act, ctx| {
+                if let Some(value) = act.job_handler_map.get_mut(&job_name) {
+                    value.finish_job(log_id);
+                    if value.draining {
+                        if value.running_jobs.is_empty() {
+                            act.remove_job_handler(&job_name);
+                        }
+                    } else if value.running_jobs.is_empty() && !value.block_jobs.is_empty() {
+                        act.run_next_block_job(job_name, ctx);
                     }
-                }
-            };
-            if let Some(value) = act.job_handler_map.get_mut(&job_name) {
-                if value.last_run_id == log_id {
-                    value.is_running = false;
-                    value.last_run_id = 0;
-                }
-                if !value.block_jobs.is_empty() {
-                    act.run_next_block_job(job_name, ctx);
-                }
-            };
-        })
-        .spawn(ctx);
+                };
+            })
+            .spawn(ctx);
     }
 
     fn run_next_block_job(&mut self, job_name: Arc<String>, ctx: &mut Context<Self>) {
         let (job, run_param) = if let Some(value) = self.job_handler_map.get_mut(&job_name) {
             if let Some(job) = value.pop_block_job() {
-                value.is_running = true;
-                value.last_run_id = job.log_id;
-                (job, value.build_run_param())
+                let run_param = value.start_job(&job);
+                (job, run_param)
             } else {
                 return;
             }
@@ -152,20 +351,103 @@ impl ExecutorActor {
     fn check_idle_beat(&mut self, job_id: u64) -> anyhow::Result<ExecutorActorResult> {
         if let Some(name) = self.job_id_map.get(&job_id) {
             if let Some(handler) = self.job_handler_map.get_mut(name) {
-                if handler.is_running || !handler.block_jobs.is_empty() {
+                if handler.is_running() || !handler.block_jobs.is_empty() {
                     return Ok(ExecutorActorResult::JobRunning);
                 }
             }
         }
         Ok(ExecutorActorResult::Ok)
     }
+
+    fn log_clean_heartbeat(&self, ctx: &mut Context<Self>) {
+        let log_path = self.client_config.log_path.clone();
+        let log_retention_days = self.client_config.log_retention_days;
+        if log_path.is_empty() || log_retention_days < MIN_LOG_RETENTION_DAYS {
+            return;
+        }
+        async move {
+            let count = tokio::task::spawn_blocking(move || {
+                clean_expired_logs(&log_path, log_retention_days)
+            })
+            .await
+            .unwrap_or_default();
+            if count > 0 {
+                log::info!("clean expired job log dirs, count:{}", count);
+            }
+        }
+        .into_actor(self)
+        .map(|_res, _act, _ctx| {})
+        .spawn(ctx);
+        ctx.run_later(Duration::from_secs(24 * 60 * 60), |act, ctx| {
+            act.log_clean_heartbeat(ctx);
+        });
+    }
+
+    fn handler_status(&self) -> Vec<JobHandlerStatus> {
+        self.job_handler_map
+            .values()
+            .filter(|v| !v.draining)
+            .map(|v| JobHandlerStatus {
+                name: v.name.clone(),
+                running_jobs: v.running_jobs.len(),
+                queued_jobs: v.block_jobs.len(),
+            })
+            .collect()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.job_handler_map
+            .values()
+            .all(|v| v.running_jobs.is_empty() && v.block_jobs.is_empty())
+    }
+
+    fn kill_all_jobs(&mut self, reason: &str) -> anyhow::Result<ExecutorActorResult> {
+        for handler in self.job_handler_map.values_mut() {
+            let (_, removed_jobs) = handler.kill_all_jobs(reason);
+            self.client_state
+                .metrics()
+                .record_job_discard(&handler.name, removed_jobs.len());
+            callback_removed_jobs(&removed_jobs, reason);
+        }
+        Ok(ExecutorActorResult::Ok)
+    }
+
+    fn kill_job(&mut self, job_id: u64) -> anyhow::Result<ExecutorActorResult> {
+        let reason = "scheduling center kill job.";
+        let (kill_count, removed_jobs) = if let Some(handler) = self
+            .job_id_map
+            .get(&job_id)
+            .and_then(|name| self.job_handler_map.get_mut(name))
+        {
+            let res = handler.kill_job(job_id, reason);
+            self.client_state
+                .metrics()
+                .record_job_discard(&handler.name, res.1.len());
+            res
+        } else {
+            return Ok(ExecutorActorResult::NotFoundJob);
+        };
+        callback_removed_jobs(&removed_jobs, reason);
+        log::info!(
+            "kill job, job_id:{}, running:{}, queued:{}",
+            job_id,
+            kill_count,
+            removed_jobs.len()
+        );
+        if kill_count == 0 && removed_jobs.is_empty() {
+            Ok(ExecutorActorResult::NotFoundJob)
+        } else {
+            Ok(ExecutorActorResult::Ok)
+        }
+    }
 }
 
 impl Actor for ExecutorActor {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         log::info!("Executor actor started");
+        self.log_clean_heartbeat(ctx);
     }
 }
 
@@ -193,11 +475,179 @@ impl Handler<ExecutorActorReq> for ExecutorActor {
                 self.register_job_handler(job_handler_value);
                 Ok(ExecutorActorResult::Ok)
             }
+            ExecutorActorReq::Replace {
+                job_name,
+                job_handler,
+            } => self.replace_job_handler(job_name, job_handler),
+            ExecutorActorReq::Unregister { job_name } => self.unregister_job_handler(job_name),
             ExecutorActorReq::RunJob {
                 job_name,
                 job_content,
             } => self.run_job(job_name, job_content, ctx),
+            ExecutorActorReq::RunGlueJob {
+                job_content,
+                glue_source,
+                glue_update_time,
+            } => self.run_glue_job(job_content, glue_source, glue_update_time, ctx),
             ExecutorActorReq::IdleBeat { job_id } => self.check_idle_beat(job_id),
+            ExecutorActorReq::Kill { job_id } => self.kill_job(job_id),
+            ExecutorActorReq::CheckIdle => {
+                if self.is_idle() {
+                    Ok(ExecutorActorResult::Ok)
+                } else {
+                    Ok(ExecutorActorResult::JobRunning)
+                }
+            }
+            ExecutorActorReq::KillAll { reason } => self.kill_all_jobs(&reason),
+            ExecutorActorReq::QueryHandlers => {
+                Ok(ExecutorActorResult::Handlers(self.handler_status()))
+            }
+            ExecutorActorReq::Stop => {
+                log::info!("Executor actor stop");
+                ctx.stop();
+                System::current().stop();
+                Ok(ExecutorActorResult::Ok)
+            }
+        }
+    }
+}
+
+/// 反馈被移除的排队任务
+fn callback_removed_jobs(removed_jobs: &[JobContext], reason: &str) {
+    for job in removed_jobs {
+        job.callback_failed_with_info(
+            format!("{} [job not executed, in the job queue, killed.]", reason),
+            FAIL_CODE,
+        );
+    }
+}
+
+/// 任务终止或执行超时后，拦截器链结束前的最长等待时间
+const CANCEL_GRACE_DURATION: Duration = Duration::from_secs(3);
+
+/// 任务处理器运行期间持有，全部释放后才表示任务处理器已结束
+type RunningGuard = tokio::sync::mpsc::Sender<()>;
+
+/// 运行拦截器链与任务处理器，任务终止与执行超时作用于整个调用链；
+/// 终止或超时后调用链最多再运行`CANCEL_GRACE_DURATION`，让`on_error`处理终止错误，之后直接结束；
+async fn execute_job(
+    interceptors: Vec<Arc<dyn JobInterceptor>>,
+    job_handler: JobHandler,
+    job_context: JobContext,
+    sync_pool: WorkerPool,
+    running_guard: RunningGuard,
+) -> anyhow::Result<JobOutcome> {
+    let cancel_token = job_context.cancel_token.clone();
+    let executor_timeout = job_context.executor_timeout;
+    let chain = intercept_job(
+        interceptors,
+        job_handler,
+        job_context,
+        sync_pool,
+        running_guard,
+    );
+    let watchdog = async {
+        if let Some(timeout) = executor_timeout {
+            tokio::select! {
+                _ = tokio::time::sleep(timeout) => {
+                    //通知任务处理器与拦截器退出
+                    cancel_token.cancel_with_code(TIMEOUT_CODE, "job execute timeout ".to_owned());
+                }
+                _ = cancel_token.cancelled() => {}
+            }
+        } else {
+            cancel_token.cancelled().await;
+        }
+        tokio::time::sleep(CANCEL_GRACE_DURATION).await;
+    };
+    tokio::select! {
+        biased;
+        r = chain => r,
+        _ = watchdog => Err(cancel_token.error()),
+    }
+}
+
+/// 按拦截器链运行任务处理器
+async fn intercept_job(
+    interceptors: Vec<Arc<dyn JobInterceptor>>,
+    job_handler: JobHandler,
+    mut job_context: JobContext,
+    sync_pool: WorkerPool,
+    running_guard: RunningGuard,
+) -> anyhow::Result<JobOutcome> {
+    if interceptors.is_empty() {
+        return process_job(job_handler, job_context, sync_pool, running_guard).await;
+    }
+    let mut entered = 0;
+    let mut result = None;
+    for interceptor in interceptors.iter() {
+        match interceptor.before(&mut job_context).await {
+            Ok(None) => entered += 1,
+            Ok(Some(outcome)) => {
+                result = Some(Ok(outcome));
+                break;
+            }
+            Err(err) => {
+                result = Some(Err(err));
+                break;
+            }
+        }
+    }
+    let mut result = match result {
+        Some(v) => v,
+        None => process_job(job_handler, job_context.clone(), sync_pool, running_guard).await,
+    };
+    for interceptor in interceptors[..entered].iter().rev() {
+        result = match result {
+            Ok(outcome) => interceptor.after(&job_context, outcome).await,
+            Err(err) => interceptor.on_error(&job_context, err).await,
+        };
+    }
+    result
+}
+
+/// 运行任务处理器，处理任务终止；
+/// 同步任务线程结束前一直持有running_guard；
+async fn process_job(
+    job_handler: JobHandler,
+    job_context: JobContext,
+    sync_pool: WorkerPool,
+    running_guard: RunningGuard,
+) -> anyhow::Result<JobOutcome> {
+    let cancel_token = job_context.cancel_token.clone();
+    match job_handler {
+        JobHandler::Async(handler) => {
+            tokio::select! {
+                r = handler.process(job_context) => r,
+                _ = cancel_token.cancelled() => Err(cancel_token.error()),
+            }
+        }
+        JobHandler::Sync(handler) => {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            sync_pool
+                .execute(move || {
+                    let _running_guard = running_guard;
+                    //在线程池中排队时已被终止或超时，服务端已收到失败反馈，不再运行
+                    if job_context.is_cancelled() {
+                        tx.send(Err(job_context.cancel_token.error())).ok();
+                        return;
+                    }
+                    #[cfg(feature = "tracing")]
+                    let span = job_context.span.clone();
+                    #[cfg(feature = "tracing")]
+                    let _enter = span.enter();
+                    let ctx = handler.process(job_context);
+                    tx.send(ctx).ok();
+                })
+                .map_err(|e| JobHandleError::new(FAIL_CODE, format!("sync job {}", e)))?;
+            //同步任务不能强制终止，终止后先返回结果，线程结束后再释放running_guard
+            tokio::select! {
+                r = rx => match r {
+                    Ok(v) => v,
+                    Err(e) => Err(anyhow::anyhow!(e)),
+                },
+                _ = cancel_token.cancelled() => Err(cancel_token.error()),
+            }
         }
     }
 }
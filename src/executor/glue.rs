@@ -0,0 +1,119 @@
+use crate::common::client_config::ClientConfig;
+use crate::common::job_log::{flush_job_log, glue_src_path};
+use crate::common::model::enum_type::GlueType;
+use crate::common::model::handler::{AsyncJobHandler, JobContext, JobHandleError, JobOutcome};
+use crate::common::model::FAIL_CODE;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// GLUE脚本任务在执行器中的任务名
+pub fn glue_job_name(job_id: u64) -> Arc<String> {
+    Arc::new(format!("glue:{}", job_id))
+}
+
+/// GLUE脚本任务处理器；
+/// 脚本按glueUpdatetime写入版本文件，执行参数与java执行器保持一致：job_param shard_index shard_total；
+pub struct ScriptJobHandler {
+    job_id: u64,
+    glue_type: GlueType,
+    glue_source: String,
+    glue_update_time: u64,
+    glue_src_path: PathBuf,
+}
+
+impl ScriptJobHandler {
+    pub fn new(
+        client_config: &ClientConfig,
+        job_id: u64,
+        glue_type: GlueType,
+        glue_source: String,
+        glue_update_time: u64,
+    ) -> Self {
+        let handler = Self {
+            job_id,
+            glue_type,
+            glue_source,
+            glue_update_time,
+            glue_src_path: glue_src_path(&client_config.log_path),
+        };
+        handler.clean_old_script_file();
+        handler
+    }
+
+    fn script_file_name(&self) -> PathBuf {
+        self.glue_src_path.join(format!(
+            "{}_{}{}",
+            self.job_id,
+            self.glue_update_time,
+            self.glue_type.get_suffix()
+        ))
+    }
+
+    /// 清理同一任务旧版本的脚本文件
+    fn clean_old_script_file(&self) {
+        let dirs = match std::fs::read_dir(&self.glue_src_path) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let prefix = format!("{}_", self.job_id);
+        for entry in dirs.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                std::fs::remove_file(entry.path()).ok();
+            }
+        }
+    }
+
+    async fn mark_script_file(&self, script_file_name: &Path) -> anyhow::Result<()> {
+        if tokio::fs::try_exists(script_file_name).await? {
+            return Ok(());
+        }
+        tokio::fs::create_dir_all(&self.glue_src_path).await?;
+        tokio::fs::write(script_file_name, self.glue_source.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncJobHandler for ScriptJobHandler {
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
+        if !self.glue_type.is_script() {
+            return Err(JobHandleError::new(
+                FAIL_CODE,
+                format!("glueType[{}] invalid.", self.glue_type.to_str()),
+            )
+            .into());
+        }
+        let script_file_name = self.script_file_name();
+        self.mark_script_file(&script_file_name).await?;
+        context.log(format!(
+            "----------- script file:{} -----------",
+            script_file_name.to_string_lossy()
+        ));
+        let log_writer = context.log_writer()?;
+        //子进程直接写入日志文件，先等待之前的日志写入
+        flush_job_log().await;
+        //任务被终止或超时时释放子进程
+        let status = tokio::process::Command::new(self.glue_type.get_cmd())
+            .arg(&script_file_name)
+            .arg(context.job_param.as_deref().unwrap_or_default())
+            .arg(context.shard_index.to_string())
+            .arg(context.shard_total.to_string())
+            .stdout(log_writer.to_stdio()?)
+            .stderr(log_writer.to_stdio()?)
+            .kill_on_drop(true)
+            .status()
+            .await?;
+        match status.code() {
+            Some(0) => Ok(JobOutcome::success()),
+            Some(code) => Err(JobHandleError::new(
+                FAIL_CODE,
+                format!("script exit value({}) is failed", code),
+            )
+            .into()),
+            None => {
+                Err(JobHandleError::new(FAIL_CODE, "script terminated by signal".to_owned()).into())
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod admin_server;
+pub mod callback_log;
 pub mod core;
+pub mod glue;
 pub mod model;
@@ -1,5 +1,6 @@
+use crate::common::client_state::{AdminAddrStatus, JobHandlerStatus};
 use crate::common::model::admin_request::CallbackParam;
-use crate::common::model::handler::{JobContext, JobHandlerValue};
+use crate::common::model::handler::{JobContext, JobHandler, JobHandlerValue};
 use actix::Message;
 use std::sync::Arc;
 
@@ -8,23 +9,52 @@ use std::sync::Arc;
 pub enum ServerAccessActorReq {
     Stop,
     CallBack(Vec<CallbackParam>),
+    RegistryRemove,
+    QueryPendingCallbacks,
+    QueryStatus,
 }
 
 pub enum ServerAccessActorResult {
     None,
+    PendingCallbacks(usize),
+    Status {
+        pending_callbacks: usize,
+        admin_addrs: Vec<AdminAddrStatus>,
+    },
 }
 
 #[derive(Message, Clone)]
 #[rtype(result = "anyhow::Result<ExecutorActorResult>")]
 pub enum ExecutorActorReq {
     Register(JobHandlerValue),
+    Replace {
+        job_name: Arc<String>,
+        job_handler: JobHandler,
+    },
+    Unregister {
+        job_name: Arc<String>,
+    },
     RunJob {
         job_name: Arc<String>,
         job_content: JobContext,
     },
+    RunGlueJob {
+        job_content: JobContext,
+        glue_source: String,
+        glue_update_time: u64,
+    },
     IdleBeat {
         job_id: u64,
     },
+    Kill {
+        job_id: u64,
+    },
+    CheckIdle,
+    KillAll {
+        reason: String,
+    },
+    QueryHandlers,
+    Stop,
 }
 
 pub enum ExecutorActorResult {
@@ -32,4 +62,5 @@ pub enum ExecutorActorResult {
     NotFoundJob,
     Discard,
     JobRunning,
+    Handlers(Vec<JobHandlerStatus>),
 }
@@ -2,7 +2,38 @@ pub mod client;
 pub mod common;
 pub mod executor;
 pub mod server;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use client::builder::XxlClientBuilder;
 pub use client::client::{get_last_xxl_client, XxlClient};
-pub use common::model::handler::{AsyncJobHandler, JobContext, JobHandler, SyncJobHandler};
+#[cfg(feature = "macros")]
+pub use common::model::handler::JobHandlerRegistration;
+pub use common::model::handler::{
+    AsyncJobHandler, JobContext, JobHandleError, JobHandler, JobHandlerConfig, JobOutcome,
+    SyncJobHandler, TypedJobHandler,
+};
+pub use common::model::interceptor::JobInterceptor;
+#[cfg(feature = "macros")]
+pub use xxljob_sdk_rs_macros::xxl_job;
+
+/// `#[xxl_job]`生成代码使用的依赖，不要直接使用
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use anyhow;
+    pub use async_trait::async_trait;
+    pub use inventory;
+}
+
+/// 写入任务执行日志，用法同`format!`
+///
+/// ```ignore
+/// xxl_log!(context, "process step:{}", i);
+/// ```
+#[macro_export]
+macro_rules! xxl_log {
+    ($context:expr, $($arg:tt)+) => {
+        $context.log_at(module_path!(), line!(), &format!($($arg)+))
+    };
+}
@@ -65,8 +65,10 @@ where
         } else {
             "".to_owned()
         };
-        let is_check_success = self.share_data.client_config.access_token.is_empty()
-            || self.share_data.client_config.access_token.as_str() == token.as_str();
+        let client_config = &self.share_data.client_config;
+        let is_check_success = client_config.access_token.is_empty()
+            || client_config.access_token.as_str() == token.as_str()
+            || (!client_config.metrics_check_token && request.path() == "/metrics");
 
         let service = self.service.clone();
         Box::pin(async move {
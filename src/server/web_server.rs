@@ -2,6 +2,7 @@ use crate::common::share_data::ShareData;
 use crate::server::middle::CheckMiddle;
 use crate::server::xxlapi;
 use actix::prelude::*;
+use actix_web::dev::{Server, ServerHandle};
 use actix_web::web::{Data, ServiceConfig};
 use actix_web::{middleware, web, App, HttpServer};
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
@@ -16,28 +17,51 @@ pub fn api_config(config: &mut ServiceConfig) {
         .service(web::resource("/log").route(web::post().to(xxlapi::log)));
 }
 
-pub async fn run_embed_web(share_data: Arc<ShareData>) -> anyhow::Result<()> {
+/// 指标接口`/metrics`，Prometheus文本格式
+pub fn metrics_config(config: &mut ServiceConfig) {
+    config.service(web::resource("/metrics").route(web::get().to(xxlapi::metrics)));
+}
+
+pub fn build_embed_web(share_data: Arc<ShareData>) -> anyhow::Result<Server> {
     let http_console_addr = share_data.client_config.get_http_addr();
     log::info!("run embed server http addr:{}", &http_console_addr);
+    let metrics_enabled = share_data.client_config.metrics_enabled;
     let app_data = Data::new(share_data.clone());
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         let app_data = app_data.clone();
-        App::new()
+        let mut app = App::new()
             .app_data(app_data.clone())
             .wrap(CheckMiddle::new(app_data.as_ref().clone()))
             .wrap(middleware::Logger::default())
-            .configure(api_config)
+            .configure(api_config);
+        if metrics_enabled {
+            app = app.configure(metrics_config);
+        }
+        app
     })
     .workers(1)
     .bind(http_console_addr)?
-    .run()
-    .await
-    .ok();
+    .run();
+    Ok(server)
+}
+
+pub async fn run_embed_web(share_data: Arc<ShareData>) -> anyhow::Result<()> {
+    build_embed_web(share_data)?.await.ok();
     Ok(())
 }
 
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+pub enum ServerRunnerReq {
+    /// 停止内嵌web服务，并停止所在线程的actor系统
+    Stop,
+}
+
 #[bean(inject)]
-pub struct ServerRunner {}
+#[derive(Default)]
+pub struct ServerRunner {
+    server_handle: Option<ServerHandle>,
+}
 
 impl Actor for ServerRunner {
     type Context = Context<Self>;
@@ -59,12 +83,47 @@ impl Inject for ServerRunner {
         let share_data = Arc::new(ShareData {
             executor_actor: factory_data.get_actor().unwrap(),
             server_access_actor: factory_data.get_actor().unwrap(),
+            server_runner: ctx.address(),
             client_config: factory_data.get_bean().unwrap(),
+            client_state: factory_data.get_bean().unwrap(),
         });
-        run_embed_web(share_data)
-            .into_actor(self)
-            .map(|_res, _act, _ctx| {})
-            .spawn(ctx);
-        log::info!("api server running");
+        match build_embed_web(share_data) {
+            Ok(server) => {
+                self.server_handle = Some(server.handle());
+                server
+                    .into_actor(self)
+                    .map(|_res, _act, _ctx| {})
+                    .spawn(ctx);
+                log::info!("api server running");
+            }
+            Err(e) => {
+                log::error!("api server start error:{}", e);
+            }
+        }
+    }
+}
+
+impl Handler<ServerRunnerReq> for ServerRunner {
+    type Result = ResponseActFuture<Self, anyhow::Result<()>>;
+
+    fn handle(&mut self, msg: ServerRunnerReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            ServerRunnerReq::Stop => {
+                let server_handle = self.server_handle.take();
+                Box::pin(
+                    async move {
+                        if let Some(server_handle) = server_handle {
+                            server_handle.stop(true).await;
+                        }
+                    }
+                    .into_actor(self)
+                    .map(|_res, _act, _ctx| {
+                        log::info!("api server stopped");
+                        System::current().stop();
+                        Ok(())
+                    }),
+                )
+            }
+        }
     }
 }
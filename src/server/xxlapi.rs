@@ -1,8 +1,11 @@
-use crate::common::model::api_model::{JobIdleBeatParam, JobRunParam};
+use crate::common::job_log::{flush_job_log, make_log_file_name, read_log};
+use crate::common::model::api_model::{JobIdleBeatParam, JobRunParam, LogParam, LogResult};
+use crate::common::model::enum_type::GlueType;
 use crate::common::model::handler::JobContext;
-use crate::common::model::{xxl_api_empty_success, XxlApiResult};
+use crate::common::model::{xxl_api_empty_success, XxlApiResult, SUCCESS_CODE};
 use crate::common::share_data::ShareData;
 use crate::executor::model::{ExecutorActorReq, ExecutorActorResult};
+use actix_web::http::header::ContentType;
 use actix_web::web::Data;
 use actix_web::{web, HttpResponse, Responder};
 use std::sync::Arc;
@@ -36,6 +39,32 @@ pub(crate) async fn run(
     web::Json(run_param): web::Json<JobRunParam>,
 ) -> impl Responder {
     log::info!("run api param:{:?}", &run_param);
+    if share_data.client_state.is_shutting_down() {
+        return HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(format!(
+            "executor is shutting down,log_id:{}",
+            run_param.log_id
+        ))));
+    }
+    let glue_type = GlueType::from_str(run_param.glue_type.as_deref().unwrap_or_default())
+        .unwrap_or(GlueType::Bean);
+    if glue_type.is_script() {
+        let glue_source = run_param.glue_source.clone().unwrap_or_default();
+        let glue_update_time = run_param.glue_update_time.unwrap_or_default();
+        let job_content = JobContext::new(run_param, share_data.as_ref().clone());
+        share_data
+            .executor_actor
+            .do_send(ExecutorActorReq::RunGlueJob {
+                job_content,
+                glue_source,
+                glue_update_time,
+            });
+        return HttpResponse::Ok().json(xxl_api_empty_success());
+    } else if glue_type != GlueType::Bean {
+        return HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(format!(
+            "glueType[{}] is not valid.",
+            glue_type.to_str()
+        ))));
+    }
     let job_name = run_param.executor_handler.clone().unwrap_or_default();
     if job_name.is_empty() {
         return HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(format!(
@@ -51,13 +80,67 @@ pub(crate) async fn run(
     HttpResponse::Ok().json(xxl_api_empty_success())
 }
 
-pub(crate) async fn kill(web::Json(param): web::Json<JobIdleBeatParam>) -> impl Responder {
+pub(crate) async fn kill(
+    share_data: Data<Arc<ShareData>>,
+    web::Json(param): web::Json<JobIdleBeatParam>,
+) -> impl Responder {
     log::info!("kill api param:{:?}", &param);
-    //todo kill job
-    HttpResponse::Ok().json(xxl_api_empty_success())
+    match share_data
+        .executor_actor
+        .send(ExecutorActorReq::Kill {
+            job_id: param.job_id,
+        })
+        .await
+    {
+        Ok(Ok(ExecutorActorResult::NotFoundJob)) => HttpResponse::Ok().json(XxlApiResult::<()> {
+            content: None,
+            code: SUCCESS_CODE,
+            msg: Some("job thread already killed.".to_string()),
+        }),
+        Ok(Ok(_)) => HttpResponse::Ok().json(xxl_api_empty_success()),
+        Ok(Err(e)) => HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(e.to_string()))),
+        Err(e) => HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(e.to_string()))),
+    }
 }
 
-pub(crate) async fn log() -> impl Responder {
-    //todo load log
-    HttpResponse::Ok().json(xxl_api_empty_success())
+pub(crate) async fn log(
+    share_data: Data<Arc<ShareData>>,
+    web::Json(param): web::Json<LogParam>,
+) -> impl Responder {
+    log::debug!("log api param:{:?}", &param);
+    let log_file_name = match make_log_file_name(
+        &share_data.client_config.log_path,
+        param.log_date_tim,
+        param.log_id,
+    ) {
+        Some(v) => v,
+        None => {
+            return HttpResponse::Ok().json(XxlApiResult::success(Some(LogResult {
+                from_line_num: param.from_line_num,
+                to_line_num: 0,
+                log_content: "readLog fail, logFile not found".to_owned(),
+                is_end: true,
+            })));
+        }
+    };
+    //等待还在日志写入线程中的内容写入文件
+    flush_job_log().await;
+    match web::block(move || read_log(&log_file_name, param.from_line_num)).await {
+        Ok(v) => HttpResponse::Ok().json(XxlApiResult::success(Some(v))),
+        Err(e) => HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(e.to_string()))),
+    }
+}
+
+pub(crate) async fn metrics(share_data: Data<Arc<ShareData>>) -> impl Responder {
+    let handlers = match share_data
+        .executor_actor
+        .send(ExecutorActorReq::QueryHandlers)
+        .await
+    {
+        Ok(Ok(ExecutorActorResult::Handlers(v))) => v,
+        _ => vec![],
+    };
+    HttpResponse::Ok()
+        .content_type(ContentType::plaintext())
+        .body(share_data.client_state.metrics().render(&handlers))
 }
@@ -0,0 +1,445 @@
+use crate::common::http_utils::HttpUtils;
+use crate::common::model::admin_request::{CallbackParam, RegistryParam};
+use crate::common::model::api_model::{JobIdleBeatParam, JobRunParam, LogParam, LogResult};
+use crate::common::model::manage_model::{PageResult, XxlJobInfo};
+use crate::common::model::XxlApiResult;
+use actix_web::dev::ServerHandle;
+use actix_web::web::{Bytes, Data};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 模拟服务端收到的请求记录
+#[derive(Clone, Debug, Default)]
+pub struct MockAdminRecord {
+    pub registry: Vec<RegistryParam>,
+    pub registry_remove: Vec<RegistryParam>,
+    pub callback: Vec<CallbackParam>,
+    /// 每次回调请求携带的任务结果数量
+    pub callback_batches: Vec<usize>,
+    /// access token校验失败的请求数量
+    pub rejected: usize,
+    /// 管理接口登录成功的次数
+    pub login: usize,
+}
+
+/// 模拟服务端管理接口的账号
+pub const MOCK_ADMIN_USER_NAME: &str = "admin";
+/// 模拟服务端管理接口的密码
+pub const MOCK_ADMIN_PASSWORD: &str = "123456";
+
+const LOGIN_COOKIE_NAME: &str = "XXL_JOB_LOGIN_IDENTITY";
+
+struct MockAdminState {
+    access_token: String,
+    record: Mutex<MockAdminRecord>,
+    session_seq: AtomicU64,
+    sessions: Mutex<Vec<String>>,
+    job_infos: Mutex<Vec<XxlJobInfo>>,
+}
+
+impl MockAdminState {
+    fn check_token(&self, req: &HttpRequest) -> bool {
+        let token = req
+            .headers()
+            .get("XXL-JOB-ACCESS-TOKEN")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if self.access_token.is_empty() || self.access_token == token {
+            return true;
+        }
+        if let Ok(mut record) = self.record.lock() {
+            record.rejected += 1;
+        }
+        false
+    }
+
+    /// 管理接口按登录cookie校验会话
+    fn check_session(&self, req: &HttpRequest) -> bool {
+        let session = match req.cookie(LOGIN_COOKIE_NAME) {
+            Some(v) => v.value().to_owned(),
+            None => return false,
+        };
+        self.sessions
+            .lock()
+            .map(|v| v.contains(&session))
+            .unwrap_or_default()
+    }
+
+    fn update<F: FnOnce(&mut MockAdminRecord)>(&self, f: F) {
+        if let Ok(mut record) = self.record.lock() {
+            f(&mut record);
+        }
+    }
+}
+
+fn token_error() -> HttpResponse {
+    HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(
+        "The access token is wrong.".to_owned(),
+    )))
+}
+
+/// 与服务端一致，不依赖Content-Type，直接按json解析请求体
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> serde_json::Result<T> {
+    serde_json::from_slice(body)
+}
+
+fn body_error(e: serde_json::Error) -> HttpResponse {
+    HttpResponse::Ok().json(XxlApiResult::<()>::fail(Some(format!(
+        "The request body is invalid:{}",
+        e
+    ))))
+}
+
+async fn registry(req: HttpRequest, state: Data<MockAdminState>, body: Bytes) -> HttpResponse {
+    if !state.check_token(&req) {
+        return token_error();
+    }
+    let param: RegistryParam = match parse_body(&body) {
+        Ok(v) => v,
+        Err(e) => return body_error(e),
+    };
+    state.update(|record| record.registry.push(param));
+    HttpResponse::Ok().json(XxlApiResult::<()>::success(None))
+}
+
+async fn registry_remove(
+    req: HttpRequest,
+    state: Data<MockAdminState>,
+    body: Bytes,
+) -> HttpResponse {
+    if !state.check_token(&req) {
+        return token_error();
+    }
+    let param: RegistryParam = match parse_body(&body) {
+        Ok(v) => v,
+        Err(e) => return body_error(e),
+    };
+    state.update(|record| record.registry_remove.push(param));
+    HttpResponse::Ok().json(XxlApiResult::<()>::success(None))
+}
+
+async fn callback(req: HttpRequest, state: Data<MockAdminState>, body: Bytes) -> HttpResponse {
+    if !state.check_token(&req) {
+        return token_error();
+    }
+    let params: Vec<CallbackParam> = match parse_body(&body) {
+        Ok(v) => v,
+        Err(e) => return body_error(e),
+    };
+    state.update(|record| {
+        record.callback_batches.push(params.len());
+        record.callback.extend(params);
+    });
+    HttpResponse::Ok().json(XxlApiResult::<()>::success(None))
+}
+
+/// 与服务端一致，管理接口的请求体为表单
+fn parse_form(body: &[u8]) -> HashMap<String, String> {
+    serde_urlencoded::from_bytes(body).unwrap_or_default()
+}
+
+async fn login(state: Data<MockAdminState>, body: Bytes) -> HttpResponse {
+    let form = parse_form(&body);
+    if form.get("userName").map(|v| v.as_str()) != Some(MOCK_ADMIN_USER_NAME)
+        || form.get("password").map(|v| v.as_str()) != Some(MOCK_ADMIN_PASSWORD)
+    {
+        return HttpResponse::Ok()
+            .json(XxlApiResult::<()>::fail(Some("账号或密码错误".to_owned())));
+    }
+    let session = format!(
+        "mock-session-{}",
+        state.session_seq.fetch_add(1, Ordering::Relaxed)
+    );
+    if let Ok(mut sessions) = state.sessions.lock() {
+        sessions.push(session.clone());
+    }
+    state.update(|record| record.login += 1);
+    HttpResponse::Ok()
+        .insert_header((
+            "Set-Cookie",
+            format!("{}={}; Path=/; HttpOnly", LOGIN_COOKIE_NAME, session),
+        ))
+        .json(XxlApiResult::<()>::success(None))
+}
+
+/// 与服务端一致，没有登录时跳转到登录页
+fn redirect_login() -> HttpResponse {
+    HttpResponse::Found()
+        .insert_header(("Location", "/toLogin"))
+        .finish()
+}
+
+async fn to_login() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html")
+        .body("<html><body>login</body></html>")
+}
+
+/// 服务端返回的任务包含调度状态，XxlJobInfo序列化时不包含这些字段
+fn job_info_json(job_info: &XxlJobInfo) -> serde_json::Value {
+    let mut value = serde_json::to_value(job_info).unwrap_or_default();
+    if let Some(map) = value.as_object_mut() {
+        map.insert("triggerStatus".to_owned(), job_info.trigger_status.into());
+        map.insert(
+            "triggerLastTime".to_owned(),
+            job_info.trigger_last_time.into(),
+        );
+        map.insert(
+            "triggerNextTime".to_owned(),
+            job_info.trigger_next_time.into(),
+        );
+    }
+    value
+}
+
+async fn job_info_page_list(
+    req: HttpRequest,
+    state: Data<MockAdminState>,
+    body: Bytes,
+) -> HttpResponse {
+    if !state.check_session(&req) {
+        return redirect_login();
+    }
+    let form = parse_form(&body);
+    let job_group: i32 = form
+        .get("jobGroup")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default();
+    let data: Vec<serde_json::Value> = state
+        .job_infos
+        .lock()
+        .map(|v| {
+            v.iter()
+                .filter(|info| job_group == 0 || info.job_group == job_group)
+                .map(job_info_json)
+                .collect()
+        })
+        .unwrap_or_default();
+    HttpResponse::Ok().json(PageResult {
+        records_total: data.len() as u64,
+        records_filtered: data.len() as u64,
+        data,
+    })
+}
+
+/// 用于集成测试的模拟服务端(xxl-job-admin)；
+/// 接收执行器的注册、注销与回调请求并记录下来，也可以向已注册的执行器发起触发、终止、忙碌检测与查询日志请求；
+/// 管理接口只支持`/login`与`/jobinfo/pageList`，账号为`MOCK_ADMIN_USER_NAME`与`MOCK_ADMIN_PASSWORD`；
+///
+/// ```ignore
+/// let admin = MockAdminServer::start("default_token")?;
+/// let client = XxlClientBuilder::new(admin.admin_address())
+///     .set_access_token("default_token".to_owned())
+///     .build()?;
+/// let executor = admin.wait_registry(Duration::from_secs(5)).await.unwrap();
+/// admin.run(&executor.registry_value, JobRunParam { .. }).await?;
+/// let callback = admin.wait_callback(log_id, Duration::from_secs(5)).await;
+/// ```
+pub struct MockAdminServer {
+    addr: SocketAddr,
+    access_token: String,
+    state: Data<MockAdminState>,
+    server_handle: ServerHandle,
+    client: reqwest::Client,
+}
+
+impl MockAdminServer {
+    /// 在新线程中启动模拟服务端，监听127.0.0.1的随机端口；access_token为空时不校验
+    pub fn start(access_token: &str) -> anyhow::Result<Self> {
+        let state = Data::new(MockAdminState {
+            access_token: access_token.to_owned(),
+            record: Mutex::new(MockAdminRecord::default()),
+            session_seq: AtomicU64::new(1),
+            sessions: Mutex::new(vec![]),
+            job_infos: Mutex::new(vec![]),
+        });
+        let server_state = state.clone();
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        std::thread::spawn(move || {
+            let rt = actix_rt::System::new();
+            rt.block_on(async move {
+                let server = match HttpServer::new(move || {
+                    App::new()
+                        .app_data(server_state.clone())
+                        .route("/api/registry", web::post().to(registry))
+                        .route("/api/registryRemove", web::post().to(registry_remove))
+                        .route("/api/callback", web::post().to(callback))
+                        .route("/login", web::post().to(login))
+                        .route("/toLogin", web::get().to(to_login))
+                        .route("/jobinfo/pageList", web::post().to(job_info_page_list))
+                })
+                .workers(1)
+                .bind("127.0.0.1:0")
+                {
+                    Ok(v) => v,
+                    Err(e) => {
+                        tx.send(Err(anyhow::anyhow!(e))).ok();
+                        return;
+                    }
+                };
+                let addr = server.addrs()[0];
+                let server = server.run();
+                tx.send(Ok((addr, server.handle()))).ok();
+                server.await.ok();
+            });
+        });
+        let (addr, server_handle) = rx.recv()??;
+        Ok(Self {
+            addr,
+            access_token: access_token.to_owned(),
+            state,
+            server_handle,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 服务端地址，用于`XxlClientBuilder::new`
+    pub fn admin_address(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 收到的请求记录
+    pub fn record(&self) -> MockAdminRecord {
+        self.state
+            .record
+            .lock()
+            .map(|v| v.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn clear_record(&self) {
+        self.state
+            .update(|record| *record = MockAdminRecord::default());
+    }
+
+    /// 添加任务，可通过管理接口`/jobinfo/pageList`查询
+    pub fn add_job_info(&self, job_info: XxlJobInfo) {
+        if let Ok(mut job_infos) = self.state.job_infos.lock() {
+            job_infos.push(job_info);
+        }
+    }
+
+    /// 清空管理接口的登录会话，模拟登录失效
+    pub fn expire_sessions(&self) {
+        if let Ok(mut sessions) = self.state.sessions.lock() {
+            sessions.clear();
+        }
+    }
+
+    /// 等待执行器注册，返回第一个注册请求
+    pub async fn wait_registry(&self, timeout: Duration) -> Option<RegistryParam> {
+        self.wait_for(timeout, |record| record.registry.first().cloned())
+            .await
+    }
+
+    /// 等待执行器注销
+    pub async fn wait_registry_remove(&self, timeout: Duration) -> Option<RegistryParam> {
+        self.wait_for(timeout, |record| record.registry_remove.first().cloned())
+            .await
+    }
+
+    /// 等待指定log_id的任务结果回调
+    pub async fn wait_callback(&self, log_id: u64, timeout: Duration) -> Option<CallbackParam> {
+        self.wait_for(timeout, |record| {
+            record.callback.iter().find(|v| v.log_id == log_id).cloned()
+        })
+        .await
+    }
+
+    async fn wait_for<T, F>(&self, timeout: Duration, f: F) -> Option<T>
+    where
+        F: Fn(&MockAdminRecord) -> Option<T>,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(v) = f(&self.record()) {
+                return Some(v);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// 触发任务；executor_address为执行器注册的地址
+    pub async fn run(
+        &self,
+        executor_address: &str,
+        param: JobRunParam,
+    ) -> anyhow::Result<XxlApiResult<String>> {
+        self.call_executor(executor_address, "run", &param).await
+    }
+
+    /// 终止任务
+    pub async fn kill(
+        &self,
+        executor_address: &str,
+        job_id: u64,
+    ) -> anyhow::Result<XxlApiResult<String>> {
+        self.call_executor(executor_address, "kill", &JobIdleBeatParam { job_id })
+            .await
+    }
+
+    /// 忙碌检测
+    pub async fn idle_beat(
+        &self,
+        executor_address: &str,
+        job_id: u64,
+    ) -> anyhow::Result<XxlApiResult<String>> {
+        self.call_executor(executor_address, "idleBeat", &JobIdleBeatParam { job_id })
+            .await
+    }
+
+    /// 查询任务执行日志
+    pub async fn log(
+        &self,
+        executor_address: &str,
+        param: LogParam,
+    ) -> anyhow::Result<XxlApiResult<LogResult>> {
+        self.call_executor(executor_address, "log", &param).await
+    }
+
+    async fn call_executor<P, R>(
+        &self,
+        executor_address: &str,
+        sub_url: &str,
+        param: &P,
+    ) -> anyhow::Result<XxlApiResult<R>>
+    where
+        P: Serialize,
+        R: DeserializeOwned + Default,
+    {
+        let url = format!("{}/{}", executor_address.trim_end_matches('/'), sub_url);
+        let mut headers = HashMap::new();
+        headers.insert("Content-Type".to_owned(), "application/json".to_owned());
+        if !self.access_token.is_empty() {
+            headers.insert("XXL-JOB-ACCESS-TOKEN".to_owned(), self.access_token.clone());
+        }
+        let resp = HttpUtils::request(
+            &self.client,
+            "POST",
+            &url,
+            serde_json::to_vec(param)?,
+            Some(&headers),
+            Some(3000),
+        )
+        .await?;
+        Ok(serde_json::from_slice(&resp.body)?)
+    }
+
+    /// 停止模拟服务端
+    pub async fn stop(&self) {
+        self.server_handle.stop(true).await;
+    }
+}
@@ -0,0 +1,3 @@
+pub mod mock_admin;
+
+pub use mock_admin::{MockAdminRecord, MockAdminServer, MOCK_ADMIN_PASSWORD, MOCK_ADMIN_USER_NAME};
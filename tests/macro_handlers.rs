@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use std::time::Duration;
+use xxljob_sdk_rs::common::model::api_model::JobRunParam;
+use xxljob_sdk_rs::common::model::SUCCESS_CODE;
+use xxljob_sdk_rs::common::now_millis;
+use xxljob_sdk_rs::testing::MockAdminServer;
+use xxljob_sdk_rs::{xxl_job, JobContext, JobOutcome, XxlClientBuilder};
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 模拟通过其它crate重新导出使用
+mod reexport {
+    pub use xxljob_sdk_rs as sdk;
+}
+
+#[xxl_job("macroAsyncJobHandler")]
+async fn macro_async_job(context: JobContext) -> anyhow::Result<JobOutcome> {
+    Ok(JobOutcome::success_with_msg(format!(
+        "async:{}",
+        context.job_param.unwrap_or_default()
+    )))
+}
+
+#[xxl_job("macroSyncJobHandler", crate = reexport::sdk)]
+fn macro_sync_job(context: JobContext) -> anyhow::Result<JobOutcome> {
+    Ok(JobOutcome::success_with_msg(format!(
+        "sync:{}",
+        context.job_param.unwrap_or_default()
+    )))
+}
+
+fn run_param(handler: &str, job_id: u64, log_id: u64) -> JobRunParam {
+    JobRunParam {
+        job_id,
+        log_id,
+        executor_handler: Some(Arc::new(handler.to_owned())),
+        executor_params: Some("p".to_owned()),
+        glue_type: Some("BEAN".to_owned()),
+        log_date_time: Some(now_millis()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn auto_register_macro_handlers() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = XxlClientBuilder::new(admin.admin_address())
+        .set_access_token("default_token".to_owned())
+        .set_app_name("xxl-job-test".to_owned())
+        .set_ip("127.0.0.1".to_owned())
+        .set_port(19240)
+        .build()
+        .unwrap();
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let mut names: Vec<String> = client
+        .handlers()
+        .await
+        .unwrap()
+        .iter()
+        .map(|v| v.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["macroAsyncJobHandler", "macroSyncJobHandler"]);
+
+    admin
+        .run(&executor, run_param("macroAsyncJobHandler", 1, 101))
+        .await
+        .unwrap();
+    admin
+        .run(&executor, run_param("macroSyncJobHandler", 2, 201))
+        .await
+        .unwrap();
+    let callback = admin.wait_callback(101, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(callback.handle_msg.as_deref(), Some("async:p"));
+    let callback = admin.wait_callback(201, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(callback.handle_msg.as_deref(), Some("sync:p"));
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use xxljob_sdk_rs::client::manage_client::AdminManageClient;
+use xxljob_sdk_rs::common::client_config::ClientConfig;
+use xxljob_sdk_rs::common::model::manage_model::{JobInfoQuery, XxlJobInfo};
+use xxljob_sdk_rs::testing::{MockAdminServer, MOCK_ADMIN_PASSWORD, MOCK_ADMIN_USER_NAME};
+
+fn manage_client(admin: &MockAdminServer, password: &str) -> AdminManageClient {
+    let client_config = Arc::new(ClientConfig {
+        server_address: Arc::new(admin.admin_address()),
+        ..Default::default()
+    });
+    AdminManageClient::new(
+        client_config,
+        MOCK_ADMIN_USER_NAME.to_owned(),
+        password.to_owned(),
+    )
+    .unwrap()
+}
+
+fn job_info(id: u64, job_group: i32) -> XxlJobInfo {
+    XxlJobInfo {
+        id,
+        job_group,
+        job_desc: format!("job-{}", id),
+        executor_handler: Some("demoJobHandler".to_owned()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn login_with_wrong_password() {
+    let admin = MockAdminServer::start("").unwrap();
+    let client = manage_client(&admin, "wrong");
+    let err = client
+        .job_info_page_list(&JobInfoQuery::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().starts_with("admin login failed"), "{}", err);
+    assert_eq!(admin.record().login, 0);
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn session_cookie_and_relogin() {
+    let admin = MockAdminServer::start("").unwrap();
+    admin.add_job_info(job_info(1, 1));
+    admin.add_job_info(job_info(2, 2));
+    let client = manage_client(&admin, MOCK_ADMIN_PASSWORD);
+
+    //首次请求前自动登录
+    let query = JobInfoQuery {
+        job_group: 2,
+        ..Default::default()
+    };
+    let page = client.job_info_page_list(&query).await.unwrap();
+    assert_eq!(page.records_total, 1);
+    assert_eq!(page.data[0].id, 2);
+    assert_eq!(page.data[0].job_desc, "job-2");
+    assert_eq!(admin.record().login, 1);
+
+    //之后的请求复用会话cookie
+    let page = client
+        .job_info_page_list(&JobInfoQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(page.records_total, 2);
+    assert_eq!(admin.record().login, 1);
+
+    //登录失效后服务端跳转到登录页，重新登录后再请求一次
+    admin.expire_sessions();
+    let page = client.job_info_page_list(&query).await.unwrap();
+    assert_eq!(page.records_total, 1);
+    assert_eq!(admin.record().login, 2);
+    admin.stop().await;
+}
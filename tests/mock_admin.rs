@@ -0,0 +1,710 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use xxljob_sdk_rs::common::client_state::{JobHandlerStatus, RegistryState};
+use xxljob_sdk_rs::common::model::api_model::{JobRunParam, LogParam};
+use xxljob_sdk_rs::common::model::enum_type::ExecutorBlockStrategy;
+use xxljob_sdk_rs::common::model::{FAIL_CODE, SUCCESS_CODE, TIMEOUT_CODE};
+use xxljob_sdk_rs::common::now_millis;
+use xxljob_sdk_rs::testing::MockAdminServer;
+use xxljob_sdk_rs::{
+    AsyncJobHandler, JobContext, JobHandler, JobHandlerConfig, JobInterceptor, JobOutcome,
+    SyncJobHandler, TypedJobHandler, XxlClient, XxlClientBuilder,
+};
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct TestJobHandler;
+
+#[async_trait]
+impl AsyncJobHandler for TestJobHandler {
+    async fn process(&self, context: JobContext) -> anyhow::Result<JobOutcome> {
+        if context.job_param.as_deref() == Some("sleep") {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }
+        context.log("test job done");
+        Ok(JobOutcome::success_with_msg("done"))
+    }
+}
+
+/// 忽略终止标记的同步任务，用于验证线程结束前任务仍标记为运行中
+struct SleepSyncJobHandler;
+
+impl SyncJobHandler for SleepSyncJobHandler {
+    fn process(&self, _context: JobContext) -> anyhow::Result<JobOutcome> {
+        std::thread::sleep(Duration::from_secs(2));
+        Ok(JobOutcome::success())
+    }
+}
+
+/// 记录运行次数的同步任务
+struct CountSyncJobHandler(Arc<AtomicUsize>);
+
+impl SyncJobHandler for CountSyncJobHandler {
+    fn process(&self, _context: JobContext) -> anyhow::Result<JobOutcome> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(JobOutcome::success())
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct TypedParam {
+    id: u64,
+}
+
+struct TypedTestJobHandler;
+
+#[async_trait]
+impl TypedJobHandler<TypedParam> for TypedTestJobHandler {
+    async fn process(&self, _context: JobContext, param: TypedParam) -> anyhow::Result<JobOutcome> {
+        Ok(JobOutcome::success_with_msg(format!("id:{}", param.id)))
+    }
+}
+
+/// 任务参数为hang-before时在before中一直等待
+struct HangInterceptor;
+
+#[async_trait]
+impl JobInterceptor for HangInterceptor {
+    async fn before(&self, context: &mut JobContext) -> anyhow::Result<Option<JobOutcome>> {
+        if context.job_param.as_deref() == Some("hang-before") {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+        Ok(None)
+    }
+}
+
+fn client_builder(admin: &MockAdminServer, access_token: &str, port: u16) -> XxlClientBuilder {
+    let log_path = std::env::temp_dir().join(format!("xxljob-sdk-rs-test-{}", port));
+    XxlClientBuilder::new(admin.admin_address())
+        .set_access_token(access_token.to_owned())
+        .set_app_name("xxl-job-test".to_owned())
+        .set_ip("127.0.0.1".to_owned())
+        .set_port(port)
+        .set_log_path(log_path.to_string_lossy().to_string())
+}
+
+fn build_client(admin: &MockAdminServer, access_token: &str, port: u16) -> Arc<XxlClient> {
+    register_handlers(client_builder(admin, access_token, port).build().unwrap())
+}
+
+fn register_handlers(client: Arc<XxlClient>) -> Arc<XxlClient> {
+    client
+        .register(
+            Arc::new("testJobHandler".to_owned()),
+            JobHandler::Async(Arc::new(TestJobHandler)),
+        )
+        .unwrap();
+    client
+        .register(
+            Arc::new("syncSleepJobHandler".to_owned()),
+            JobHandler::Sync(Arc::new(SleepSyncJobHandler)),
+        )
+        .unwrap();
+    client
+        .register(
+            Arc::new("typedJobHandler".to_owned()),
+            JobHandler::typed(Arc::new(TypedTestJobHandler)),
+        )
+        .unwrap();
+    client
+}
+
+fn run_param(job_id: u64, log_id: u64, param: &str) -> JobRunParam {
+    JobRunParam {
+        job_id,
+        log_id,
+        executor_handler: Some(Arc::new("testJobHandler".to_owned())),
+        executor_params: Some(param.to_owned()),
+        glue_type: Some("BEAN".to_owned()),
+        log_date_time: Some(now_millis()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn registry_run_and_callback() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19110);
+    let registry = admin.wait_registry(WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(registry.registry_key.as_str(), "xxl-job-test");
+    let executor = registry.registry_value.clone();
+    assert_eq!(
+        executor.as_str(),
+        client.status().await.unwrap().executor_address
+    );
+
+    let result = admin.run(&executor, run_param(1, 101, "")).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    let callback = admin.wait_callback(101, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(callback.handle_msg.as_deref(), Some("done"));
+
+    let result = admin.idle_beat(&executor, 1).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    client.wait_shutdown().await.unwrap();
+    assert!(admin.wait_registry_remove(WAIT_TIMEOUT).await.is_some());
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn registry_and_callback_without_token() {
+    let admin = MockAdminServer::start("").unwrap();
+    let client = build_client(&admin, "", 19120);
+    let registry = admin.wait_registry(WAIT_TIMEOUT).await.unwrap();
+    admin
+        .run(&registry.registry_value, run_param(2, 201, ""))
+        .await
+        .unwrap();
+    let callback = admin.wait_callback(201, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(admin.record().rejected, 0);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn idle_beat_and_kill_running_job() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19130);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    admin
+        .run(&executor, run_param(3, 301, "sleep"))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let result = admin.idle_beat(&executor, 3).await.unwrap();
+    assert_eq!(result.code, FAIL_CODE);
+
+    let result = admin.kill(&executor, 3).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    let callback = admin.wait_callback(301, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert!(callback.handle_msg.unwrap_or_default().contains("kill"));
+
+    let result = admin.idle_beat(&executor, 3).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn read_job_log() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19140);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let param = run_param(4, 401, "log-param");
+    let log_date_tim = param.log_date_time.unwrap() as i64;
+    admin.run(&executor, param).await.unwrap();
+    admin.wait_callback(401, WAIT_TIMEOUT).await.unwrap();
+
+    let result = admin
+        .log(
+            &executor,
+            LogParam {
+                log_date_tim,
+                log_id: 401,
+                from_line_num: 1,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    let log = result.content.unwrap();
+    assert!(log.log_content.contains("log-param"));
+    assert!(log.log_content.contains("test job done"));
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn reject_wrong_token() {
+    let admin = MockAdminServer::start("admin_token").unwrap();
+    let client = build_client(&admin, "executor_token", 19150);
+    let executor = client.status().await.unwrap().executor_address;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let record = admin.record();
+    assert!(record.registry.is_empty());
+    assert!(record.rejected > 0);
+
+    //模拟服务端使用自己的token调用执行器，执行器拒绝请求
+    let result = admin.run(&executor, run_param(5, 501, "")).await.unwrap();
+    assert_eq!(result.code, FAIL_CODE);
+    assert_eq!(result.msg.as_deref(), Some("access-token is error"));
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn sync_job_running_until_thread_finished() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19160);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let param = JobRunParam {
+        executor_handler: Some(Arc::new("syncSleepJobHandler".to_owned())),
+        executor_timeout: Some(1),
+        ..run_param(6, 601, "")
+    };
+    admin.run(&executor, param).await.unwrap();
+    let callback = admin.wait_callback(601, WAIT_TIMEOUT).await.unwrap();
+    assert_ne!(callback.handle_code, SUCCESS_CODE);
+
+    //超时回调后同步任务线程还在运行
+    let result = admin.idle_beat(&executor, 6).await.unwrap();
+    assert_eq!(result.code, FAIL_CODE);
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let result = admin.idle_beat(&executor, 6).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn executor_timeout_covers_interceptors() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = register_handlers(
+        client_builder(&admin, "default_token", 19170)
+            .add_interceptor(Arc::new(HangInterceptor))
+            .build()
+            .unwrap(),
+    );
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let param = JobRunParam {
+        executor_timeout: Some(1),
+        ..run_param(7, 701, "hang-before")
+    };
+    admin.run(&executor, param).await.unwrap();
+    //超时后拦截器链还有一段结束等待时间
+    let callback = admin
+        .wait_callback(701, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(callback.handle_code, TIMEOUT_CODE);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let result = admin.idle_beat(&executor, 7).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn typed_job_param() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19180);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let typed_param = |log_id, param: &str| JobRunParam {
+        executor_handler: Some(Arc::new("typedJobHandler".to_owned())),
+        ..run_param(8, log_id, param)
+    };
+    admin
+        .run(&executor, typed_param(801, "id=7"))
+        .await
+        .unwrap();
+    let callback = admin.wait_callback(801, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(callback.handle_msg.as_deref(), Some("id:7"));
+
+    //参数格式错误时不调用任务处理器，直接反馈解析错误
+    admin
+        .run(&executor, typed_param(802, "id=abc"))
+        .await
+        .unwrap();
+    let callback = admin.wait_callback(802, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    let msg = callback.handle_msg.unwrap_or_default();
+    assert!(msg.starts_with("job param parse error:"), "{}", msg);
+    assert!(msg.ends_with("param:id=abc"), "{}", msg);
+    assert!(msg.contains("invalid digit found in string"), "{}", msg);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn killed_sync_job_in_pool_queue_not_run() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = register_handlers(
+        client_builder(&admin, "default_token", 19190)
+            .set_sync_pool_max_threads(1)
+            .set_sync_pool_queue_size(1)
+            .build()
+            .unwrap(),
+    );
+    let run_count = Arc::new(AtomicUsize::new(0));
+    client
+        .register(
+            Arc::new("syncCountJobHandler".to_owned()),
+            JobHandler::Sync(Arc::new(CountSyncJobHandler(run_count.clone()))),
+        )
+        .unwrap();
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    //唯一的线程被占用，第二个任务在线程池中排队
+    let param = JobRunParam {
+        executor_handler: Some(Arc::new("syncSleepJobHandler".to_owned())),
+        ..run_param(9, 901, "")
+    };
+    admin.run(&executor, param).await.unwrap();
+    let param = JobRunParam {
+        executor_handler: Some(Arc::new("syncCountJobHandler".to_owned())),
+        ..run_param(10, 1001, "")
+    };
+    admin.run(&executor, param).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let result = admin.kill(&executor, 10).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    let callback = admin.wait_callback(1001, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert!(callback.handle_msg.unwrap_or_default().contains("kill"));
+
+    let callback = admin.wait_callback(901, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let result = admin.idle_beat(&executor, 10).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    assert_eq!(run_count.load(Ordering::SeqCst), 0);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn forced_block_strategy() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19200);
+    client
+        .register_with_config(
+            Arc::new("discardLaterJobHandler".to_owned()),
+            JobHandler::Async(Arc::new(TestJobHandler)),
+            JobHandlerConfig::new().set_block_strategy(ExecutorBlockStrategy::DiscardLater),
+        )
+        .unwrap();
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let serial_param = |log_id, param: &str| JobRunParam {
+        executor_handler: Some(Arc::new("discardLaterJobHandler".to_owned())),
+        executor_block_strategy: Some("SERIAL_EXECUTION".to_owned()),
+        ..run_param(11, log_id, param)
+    };
+    admin
+        .run(&executor, serial_param(1101, "sleep"))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    //服务端下发串行执行，任务处理器强制使用丢弃后续调度
+    admin.run(&executor, serial_param(1102, "")).await.unwrap();
+    let callback = admin.wait_callback(1102, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert!(callback
+        .handle_msg
+        .unwrap_or_default()
+        .starts_with("Discard the job"));
+
+    admin.kill(&executor, 11).await.unwrap();
+    admin.wait_callback(1101, WAIT_TIMEOUT).await.unwrap();
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn cover_early_waits_for_running_job() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19220);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let cover_param = |log_id| JobRunParam {
+        executor_handler: Some(Arc::new("syncSleepJobHandler".to_owned())),
+        executor_block_strategy: Some("COVER_EARLY".to_owned()),
+        ..run_param(13, log_id, "")
+    };
+    admin.run(&executor, cover_param(1301)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    admin.run(&executor, cover_param(1302)).await.unwrap();
+    let callback = admin.wait_callback(1301, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    let msg = callback.handle_msg.unwrap_or_default();
+    assert!(msg.contains("covered by log_id:1302"), "{}", msg);
+
+    //排队中的覆盖任务被更新的覆盖任务替换
+    let start = std::time::Instant::now();
+    admin.run(&executor, cover_param(1303)).await.unwrap();
+    let callback = admin.wait_callback(1302, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    let msg = callback.handle_msg.unwrap_or_default();
+    assert!(msg.contains("covered by log_id:1303"), "{}", msg);
+    assert!(msg.contains("job not executed"), "{}", msg);
+
+    //同步任务线程结束后才运行新任务
+    let callback = admin
+        .wait_callback(1303, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert!(start.elapsed() >= Duration::from_millis(3000));
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+fn handler_names(handlers: &[JobHandlerStatus]) -> Vec<String> {
+    let mut names: Vec<String> = handlers.iter().map(|v| v.name.to_string()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn unregister_and_replace_handler() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19230);
+    let job_name = Arc::new("drainJobHandler".to_owned());
+    client
+        .register(
+            job_name.clone(),
+            JobHandler::Async(Arc::new(TestJobHandler)),
+        )
+        .unwrap();
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    assert_eq!(
+        handler_names(&client.handlers().await.unwrap()),
+        vec![
+            "drainJobHandler",
+            "syncSleepJobHandler",
+            "testJobHandler",
+            "typedJobHandler"
+        ]
+    );
+    let drain_param = |job_id, log_id, param: &str| JobRunParam {
+        executor_handler: Some(job_name.clone()),
+        executor_block_strategy: Some("SERIAL_EXECUTION".to_owned()),
+        ..run_param(job_id, log_id, param)
+    };
+    admin
+        .run(&executor, drain_param(14, 1401, "sleep"))
+        .await
+        .unwrap();
+    admin
+        .run(&executor, drain_param(14, 1402, ""))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let status = client.handlers().await.unwrap();
+    let status = status.iter().find(|v| v.name == job_name).unwrap();
+    assert_eq!((status.running_jobs, status.queued_jobs), (1, 1));
+
+    //排队中的任务按失败反馈，运行中的任务仍可检测与终止
+    client.unregister(job_name.clone()).await.unwrap();
+    let callback = admin.wait_callback(1402, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert!(callback
+        .handle_msg
+        .unwrap_or_default()
+        .contains("job handler unregistered"));
+    assert!(!handler_names(&client.handlers().await.unwrap()).contains(&job_name.to_string()));
+    assert!(client.unregister(job_name.clone()).await.is_err());
+    assert!(client
+        .replace(
+            job_name.clone(),
+            JobHandler::Async(Arc::new(TestJobHandler))
+        )
+        .await
+        .is_err());
+    let result = admin.idle_beat(&executor, 14).await.unwrap();
+    assert_eq!(result.code, FAIL_CODE);
+
+    //重新注册后，新调度的任务等旧的运行中任务结束后才执行
+    let run_count = Arc::new(AtomicUsize::new(0));
+    client
+        .register(
+            job_name.clone(),
+            JobHandler::Sync(Arc::new(CountSyncJobHandler(run_count.clone()))),
+        )
+        .unwrap();
+    admin
+        .run(&executor, drain_param(15, 1501, ""))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(run_count.load(Ordering::SeqCst), 0);
+    let result = admin.kill(&executor, 14).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    let callback = admin.wait_callback(1401, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert!(callback.handle_msg.unwrap_or_default().contains("kill"));
+    let callback = admin.wait_callback(1501, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert_eq!(run_count.load(Ordering::SeqCst), 1);
+
+    //替换后使用新的任务处理器
+    client
+        .replace(
+            job_name.clone(),
+            JobHandler::Async(Arc::new(TestJobHandler)),
+        )
+        .await
+        .unwrap();
+    admin
+        .run(&executor, drain_param(15, 1502, ""))
+        .await
+        .unwrap();
+    let callback = admin.wait_callback(1502, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_msg.as_deref(), Some("done"));
+    assert_eq!(run_count.load(Ordering::SeqCst), 1);
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn glue_shell_script() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19210);
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    let param = JobRunParam {
+        executor_handler: None,
+        glue_type: Some("GLUE_SHELL".to_owned()),
+        glue_source: Some("#!/bin/bash\necho \"args:$1|$2|$3\"\nexit 3\n".to_owned()),
+        glue_update_time: Some(now_millis()),
+        broadcast_index: Some(1),
+        broadcast_total: Some(3),
+        ..run_param(12, 1201, "p1")
+    };
+    let log_date_tim = param.log_date_time.unwrap() as i64;
+    let result = admin.run(&executor, param).await.unwrap();
+    assert_eq!(result.code, SUCCESS_CODE);
+    //脚本退出码非0时按失败反馈
+    let callback = admin.wait_callback(1201, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, FAIL_CODE);
+    assert_eq!(
+        callback.handle_msg.as_deref(),
+        Some("script exit value(3) is failed")
+    );
+
+    //脚本输出写入执行日志，参数依次为任务参数、分片序号、分片总数
+    let result = admin
+        .log(
+            &executor,
+            LogParam {
+                log_date_tim,
+                log_id: 1201,
+                from_line_num: 1,
+            },
+        )
+        .await
+        .unwrap();
+    let log = result.content.unwrap();
+    assert!(
+        log.log_content.contains("args:p1|1|3"),
+        "{}",
+        log.log_content
+    );
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn registry_state_around_shutdown() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = build_client(&admin, "default_token", 19250);
+    let mut registry_status = client.subscribe_registry_status();
+    assert_eq!(registry_status.borrow().state, RegistryState::Unregistered);
+
+    tokio::time::timeout(
+        WAIT_TIMEOUT,
+        registry_status.wait_for(|v| v.state == RegistryState::Registered),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let status = client.status().await.unwrap();
+    assert_eq!(status.registry.state, RegistryState::Registered);
+    assert!(status.registry.last_registry_time.is_some());
+    assert!(status.registry.last_error.is_none());
+
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    assert_eq!(registry_status.borrow().state, RegistryState::Removed);
+    let removed = admin.wait_registry_remove(WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(removed.registry_key.as_str(), "xxl-job-test");
+    admin.stop().await;
+}
+
+#[tokio::test]
+async fn callback_batch_size_and_window() {
+    let admin = MockAdminServer::start("default_token").unwrap();
+    let client = register_handlers(
+        client_builder(&admin, "default_token", 19260)
+            .set_callback_batch_size(3)
+            .set_callback_batch_window(Duration::from_secs(1))
+            .build()
+            .unwrap(),
+    );
+    let executor = admin
+        .wait_registry(WAIT_TIMEOUT)
+        .await
+        .unwrap()
+        .registry_value;
+    //达到批量大小后合并为一次回调请求
+    for job_id in 16..19 {
+        admin
+            .run(&executor, run_param(job_id, job_id * 100 + 1, ""))
+            .await
+            .unwrap();
+    }
+    for log_id in [1601, 1701, 1801] {
+        let callback = admin.wait_callback(log_id, WAIT_TIMEOUT).await.unwrap();
+        assert_eq!(callback.handle_code, SUCCESS_CODE);
+    }
+    assert_eq!(admin.record().callback_batches, vec![3]);
+
+    //等上一个时间窗口结束，不足批量大小的结果在新的时间窗口后回调
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let start = std::time::Instant::now();
+    admin.run(&executor, run_param(16, 1602, "")).await.unwrap();
+    let callback = admin.wait_callback(1602, WAIT_TIMEOUT).await.unwrap();
+    assert_eq!(callback.handle_code, SUCCESS_CODE);
+    assert!(start.elapsed() >= Duration::from_millis(900));
+    assert_eq!(admin.record().callback_batches, vec![3, 1]);
+    let metrics = client.callback_batch_metrics();
+    assert_eq!((metrics.batch_count, metrics.max_batch_size), (2, 3));
+    client.shutdown(Duration::from_secs(1)).await.unwrap();
+    admin.stop().await;
+}